@@ -1,40 +1,179 @@
-use std::{process::Command, time::Duration};
+use std::{error::Error, process::Command, time::Duration};
 
 use serde::Deserialize;
 use tokio::time::timeout;
 
 use crate::objects::stat::{ItemRepo, Package};
 
+pub const DEFAULT_AUR_URL: &str = "https://aur.archlinux.org";
+
 #[derive(Debug, Deserialize)]
 struct AurSearchResponse {
-    #[serde(rename = "resultcount")]
+    #[serde(rename = "resultcount", default)]
     _result_count: usize,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
     results: Vec<AurRaw>,
 }
 
 #[derive(Debug, Deserialize)]
-struct AurRaw {
+pub struct AurRaw {
     #[serde(rename = "Name")]
-    name: String,
+    pub name: String,
     #[serde(rename = "Version")]
-    version: String,
+    pub version: String,
     #[serde(rename = "Description")]
-    description: Option<String>,
+    pub description: Option<String>,
     #[serde(rename = "NumVotes")]
-    num_votes: Option<u64>,
+    pub num_votes: Option<u64>,
     #[serde(rename = "Maintainer")]
     _maintainer: Option<String>,
+    #[serde(rename = "OutOfDate")]
+    pub out_of_date: Option<i64>,
+    #[serde(rename = "Popularity")]
+    pub popularity: Option<f64>,
+    #[serde(rename = "LastModified")]
+    pub last_modified: Option<i64>,
+    // only filled in by info lookups
+    #[serde(rename = "Depends", default)]
+    pub depends: Vec<String>,
+    #[serde(rename = "OptDepends", default)]
+    pub opt_depends: Vec<String>,
+    #[serde(rename = "MakeDepends", default)]
+    pub make_depends: Vec<String>,
+    #[serde(rename = "CheckDepends", default)]
+    pub check_depends: Vec<String>,
+    #[serde(rename = "Conflicts", default)]
+    pub conflicts: Vec<String>,
+    #[serde(rename = "Replaces", default)]
+    pub replaces: Vec<String>,
+}
+
+// aurweb rejects overly long request uris, so info lookups are split up
+pub const MAX_INFO_URL_LEN: usize = 4000;
+
+#[derive(Debug, Clone)]
+pub struct AurClient {
+    pub base_url: String,
+    pub timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl AurClient {
+    pub fn new(base_url: &str, timeout: Duration) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn git_url(&self, name: &str) -> String {
+        format!("{}/{}.git", self.base_url, name)
+    }
+
+    pub async fn get_text(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = async {
+            self.client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        };
+        Ok(timeout(self.timeout, request).await??)
+    }
+
+    // for downloads bigger than an rpc answer, with their own time limit
+    pub async fn get_bytes(&self, path: &str, limit: Duration) -> Result<Vec<u8>, Box<dyn Error>> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = async {
+            let resp = self.client.get(&url).send().await?.error_for_status()?;
+            resp.bytes().await
+        };
+        Ok(timeout(limit, request).await??.to_vec())
+    }
+
+    async fn rpc(&self, path: &str) -> Result<Vec<AurRaw>, Box<dyn Error>> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = async {
+            let resp = self.client.get(&url).send().await?;
+            let status = resp.status();
+            resp.text().await.map(|body| (status, body))
+        };
+        let (status, body) = timeout(self.timeout, request).await??;
+
+        // aurweb reports bad queries as `{"type": "error", "error": "..."}`
+        let resp: AurSearchResponse = match serde_json::from_str(&body) {
+            Ok(resp) => resp,
+            Err(_) if !status.is_success() => {
+                return Err(format!("aur rpc returned {}", status).into());
+            }
+            Err(e) => return Err(format!("malformed aur rpc response: {}", e).into()),
+        };
+        if resp.kind == "error" {
+            let msg = resp.error.unwrap_or_else(|| "unknown error".to_string());
+            return Err(format!("aur rpc error: {}", msg).into());
+        }
+        if !status.is_success() {
+            return Err(format!("aur rpc returned {}", status).into());
+        }
+        Ok(resp.results)
+    }
+
+    pub async fn search(&self, keyword: &str) -> Result<Vec<AurRaw>, Box<dyn Error>> {
+        self.rpc(&format!("/rpc/v5/search/{}", encode_arg(keyword)))
+            .await
+    }
+
+    pub async fn info(&self, names: &[String]) -> Result<Vec<AurRaw>, Box<dyn Error>> {
+        let mut results = Vec::new();
+        for path in info_paths(&self.base_url, names) {
+            results.extend(self.rpc(&path).await?);
+        }
+        Ok(results)
+    }
 }
 
-async fn search_aur(keyword: &String) -> Result<Vec<AurRaw>, Box<dyn std::error::Error>> {
-    let url = format!("https://aur.archlinux.org/rpc/v5/search/{}", keyword);
-    let client = reqwest::Client::new();
-    let resp = timeout(Duration::from_secs(10), client.get(&url).send())
-        .await??
-        .error_for_status()?
-        .json::<AurSearchResponse>()
-        .await?;
-    Ok(resp.results)
+fn encode_arg(arg: &str) -> String {
+    let mut encoded = String::with_capacity(arg.len());
+    for b in arg.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+// request paths of the batched info lookups, each one keeping the full url
+// below MAX_INFO_URL_LEN
+pub fn info_paths(base_url: &str, names: &[String]) -> Vec<String> {
+    let base = "/rpc/v5/info?";
+    let limit = MAX_INFO_URL_LEN.saturating_sub(base_url.len());
+    let mut paths = Vec::new();
+    let mut path = base.to_string();
+    for name in names {
+        let arg = format!("arg[]={}", encode_arg(name));
+        if path.len() > base.len() && path.len() + arg.len() + 1 > limit {
+            paths.push(std::mem::replace(&mut path, base.to_string()));
+        }
+        if path.len() > base.len() {
+            path.push('&');
+        }
+        path.push_str(&arg);
+    }
+    if path.len() > base.len() {
+        paths.push(path);
+    }
+    paths
 }
 
 impl From<AurRaw> for Package {
@@ -46,6 +185,17 @@ impl From<AurRaw> for Package {
             size: 0,
             repo: ItemRepo::AUR(value.num_votes.unwrap_or(0) as usize),
             descipt: value.description.unwrap_or("".to_string()),
+            provides: Vec::new(),
+            installed_size: 0,
+            popularity: value.popularity,
+            install_date: None,
+            build_date: None,
+            last_modified: value.last_modified,
+            installed_version: None,
+            upgradable: false,
+            explicit: false,
+            orphan: false,
+            shadows: None,
         }
     }
 }
@@ -58,14 +208,13 @@ async fn _is_aur_installed(pkg: &str) -> bool {
         .unwrap_or(false)
 }
 
-pub async fn get_aur_packages(keyword: String) -> Result<Vec<Package>, Box<dyn std::error::Error>> {
-    let mut aur_raw = search_aur(&keyword).await.unwrap_or_default();
-    let mut aur_pkgs: Vec<Package> = Vec::new();
-    while aur_raw.is_empty() && !keyword.is_empty() {
-        aur_raw = search_aur(&keyword).await.unwrap_or_default();
-    }
-    for raw in aur_raw {
-        aur_pkgs.push(Package::from(raw));
+pub async fn get_aur_packages(
+    client: &AurClient,
+    keyword: &str,
+) -> Result<Vec<Package>, Box<dyn Error>> {
+    if keyword.is_empty() {
+        return Ok(Vec::new());
     }
-    Ok(aur_pkgs)
+    let aur_raw = client.search(keyword).await?;
+    Ok(aur_raw.into_iter().map(Package::from).collect())
 }
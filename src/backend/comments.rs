@@ -0,0 +1,247 @@
+use std::error::Error;
+
+use crate::backend::aur::AurClient;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AurComment {
+    pub id: u64,
+    pub author: String,
+    pub date: String,
+    pub edited: Option<String>,
+    pub pinned: bool,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AurComments {
+    pub name: String,
+    pub comments: Vec<AurComment>,
+}
+
+pub async fn fetch_aur_comments(
+    client: &AurClient,
+    name: String,
+) -> Result<AurComments, Box<dyn Error>> {
+    let html = client
+        .get_text(&format!("/packages/{}?O=0&PP=50", name))
+        .await?;
+    Ok(AurComments {
+        comments: parse_comments(&html),
+        name,
+    })
+}
+
+// the package page has one `comments package-comments` div for the pinned
+// comments and one for the latest ones, each a flat list of h4 header + content div
+pub fn parse_comments(html: &str) -> Vec<AurComment> {
+    let mut comments = Vec::new();
+    for section in html
+        .split(r#"<div class="comments package-comments">"#)
+        .skip(1)
+    {
+        let pinned = section_title(section).contains("Pinned");
+        let mut rest = section;
+        while let Some(start) = rest.find(r#"<h4 id="comment-"#) {
+            rest = &rest[start + r#"<h4 id="comment-"#.len()..];
+            let Some(header_end) = rest.find("</h4>") else {
+                break;
+            };
+            let header = &rest[..header_end];
+            let id_end = header
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(header.len());
+            let Ok(id) = header[..id_end].parse::<u64>() else {
+                continue;
+            };
+            rest = &rest[header_end..];
+
+            let marker = format!(r#"<div id="comment-{}-content""#, id);
+            let content = rest
+                .find(&marker)
+                .and_then(|p| rest[p..].find('>').map(|q| p + q + 1))
+                .map(|p| html_to_text(inner_html(&rest[p..], "div")))
+                .unwrap_or_default();
+
+            let (author, date, edited) = parse_header(header);
+            comments.push(AurComment {
+                id,
+                author,
+                date,
+                edited,
+                pinned,
+                content,
+            });
+        }
+    }
+    comments
+}
+
+fn section_title(section: &str) -> String {
+    section
+        .find(r#"<span class="text">"#)
+        .map(|p| &section[p..])
+        .and_then(|s| s.find("</span>").map(|end| html_to_text(&s[..end])))
+        .unwrap_or_default()
+}
+
+// "alice commented on 2024-09-14 21:45 (UTC) (edited on ... by alice)"
+fn parse_header(header: &str) -> (String, String, Option<String>) {
+    let mut header = header.to_string();
+    while let Some(start) = header.find("<form") {
+        let end = header[start..]
+            .find("</form>")
+            .map_or(header.len(), |p| start + p + "</form>".len());
+        header.replace_range(start..end, "");
+    }
+    // skip the rest of the `<h4 id="comment-N" class="comment-header">` tag
+    let header = header.find('>').map_or("", |p| &header[p + 1..]);
+    let text = html_to_text(header);
+
+    let Some((author, rest)) = text.split_once(" commented on ") else {
+        return (String::new(), text, None);
+    };
+    let (date, edited) = match rest.find("(UTC)") {
+        Some(p) => rest.split_at(p + "(UTC)".len()),
+        None => (rest, ""),
+    };
+    let edited = edited
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    (
+        author.trim().to_string(),
+        date.trim().to_string(),
+        (!edited.is_empty()).then(|| edited.to_string()),
+    )
+}
+
+// `html` starts right after an opening `<tag ...>`, returns everything up to
+// the matching closing tag
+fn inner_html<'a>(html: &'a str, tag: &str) -> &'a str {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut depth = 1;
+    let mut pos = 0;
+    while let Some(p) = html[pos..].find('<') {
+        let at = pos + p;
+        let rest = &html[at..];
+        if rest.starts_with(&close) {
+            depth -= 1;
+            if depth == 0 {
+                return &html[..at];
+            }
+        } else if rest.starts_with(&open)
+            && rest[open.len()..].starts_with(|c: char| c == '>' || c.is_whitespace())
+        {
+            depth += 1;
+        }
+        pos = at + 1;
+    }
+    html
+}
+
+fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(tag_body) = rest.strip_prefix('<') {
+            let end = tag_body.find('>').unwrap_or(tag_body.len());
+            let tag = &tag_body[..end];
+            rest = tag_body.get(end + 1..).unwrap_or("");
+
+            let closing = tag.starts_with('/');
+            let name: String = tag
+                .trim_start_matches('/')
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_ascii_lowercase();
+            match (name.as_str(), closing) {
+                ("br", _) | ("pre", false) => {
+                    in_pre |= name == "pre";
+                    new_line(&mut out);
+                }
+                ("p" | "pre", true) => {
+                    in_pre = false;
+                    new_line(&mut out);
+                    new_line(&mut out);
+                }
+                ("li", false) => {
+                    new_line(&mut out);
+                    out.push_str("- ");
+                }
+                ("ul" | "ol", true) => new_line(&mut out),
+                _ => {}
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end]);
+            rest = &rest[end..];
+            if in_pre {
+                out.push_str(&text);
+                continue;
+            }
+            for c in text.chars() {
+                if !c.is_whitespace() {
+                    out.push(c);
+                } else if !(out.is_empty() || out.ends_with(' ') || out.ends_with('\n')) {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+
+    while out.contains("\n\n\n") {
+        out = out.replace("\n\n\n", "\n\n");
+    }
+    out.trim().to_string()
+}
+
+fn new_line(out: &mut String) {
+    while out.ends_with(' ') {
+        out.pop();
+    }
+    out.push('\n');
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(p) = rest.find('&') {
+        out.push_str(&rest[..p]);
+        rest = &rest[p..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                entity => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,183 @@
+use std::{error::Error, fs};
+
+use crate::backend::{aur::AurClient, open_alpm, open_files_alpm};
+
+const PACMAN_LOG: &str = "/var/log/pacman.log";
+
+// the relations of a package, from the sync dbs or the aur rpc
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageDeps {
+    pub name: String,
+    pub depends: Vec<String>,
+    pub optdepends: Vec<String>,
+    pub makedepends: Vec<String>,
+    pub checkdepends: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub replaces: Vec<String>,
+    // only known for installed packages
+    pub required_by: Vec<String>,
+    pub optional_for: Vec<String>,
+}
+
+impl PackageDeps {
+    // the non empty lists with their headings, in display order
+    pub fn sections(&self) -> Vec<(&'static str, &[String])> {
+        [
+            ("depends on", &self.depends),
+            ("optional", &self.optdepends),
+            ("make", &self.makedepends),
+            ("check", &self.checkdepends),
+            ("conflicts with", &self.conflicts),
+            ("replaces", &self.replaces),
+            ("required by", &self.required_by),
+            ("optional for", &self.optional_for),
+        ]
+        .into_iter()
+        .filter(|(_, list)| !list.is_empty())
+        .map(|(title, list)| (title, list.as_slice()))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageFiles {
+    pub name: String,
+    pub files: Vec<String>,
+    // listed from the local db rather than the files db
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub date: String,
+    pub action: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageHistory {
+    pub name: String,
+    // oldest first, as pacman logged them
+    pub entries: Vec<HistoryEntry>,
+}
+
+pub async fn fetch_dependencies(
+    client: &AurClient,
+    name: String,
+    aur: bool,
+) -> Result<PackageDeps, Box<dyn Error>> {
+    if !aur {
+        let deps = tokio::task::spawn_blocking(move || {
+            repo_dependencies(&name).map_err(|e| e.to_string())
+        })
+        .await??;
+        return Ok(deps);
+    }
+    let info = client.info(std::slice::from_ref(&name)).await?;
+    let raw = info
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("{} is not in the aur", name))?;
+    Ok(PackageDeps {
+        name,
+        depends: raw.depends,
+        optdepends: raw.opt_depends,
+        makedepends: raw.make_depends,
+        checkdepends: raw.check_depends,
+        conflicts: raw.conflicts,
+        replaces: raw.replaces,
+        ..Default::default()
+    })
+}
+
+fn repo_dependencies(name: &str) -> Result<PackageDeps, Box<dyn Error>> {
+    let alpm = open_alpm()?;
+    let pkg = alpm
+        .syncdbs()
+        .iter()
+        .find_map(|db| db.pkg(name).ok())
+        .ok_or_else(|| format!("{} is in no sync db", name))?;
+    let deps = |list: alpm::AlpmList<&alpm::Dep>| list.iter().map(|d| d.to_string()).collect();
+    let mut found = PackageDeps {
+        name: name.to_string(),
+        depends: deps(pkg.depends()),
+        optdepends: deps(pkg.optdepends()),
+        makedepends: deps(pkg.makedepends()),
+        checkdepends: deps(pkg.checkdepends()),
+        conflicts: deps(pkg.conflicts()),
+        replaces: deps(pkg.replaces()),
+        ..Default::default()
+    };
+    if let Ok(local) = alpm.localdb().pkg(name) {
+        found.required_by = local.required_by().iter().map(|s| s.to_string()).collect();
+        found.optional_for = local.optional_for().iter().map(|s| s.to_string()).collect();
+    }
+    Ok(found)
+}
+
+// the local db lists the files of installed packages, the others need the
+// files dbs from `pacman -Fy`
+pub fn load_files(name: &str) -> Result<PackageFiles, Box<dyn Error>> {
+    let alpm = open_alpm()?;
+    if let Ok(pkg) = alpm.localdb().pkg(name) {
+        return Ok(PackageFiles {
+            name: name.to_string(),
+            files: file_names(pkg),
+            installed: true,
+        });
+    }
+    let alpm = open_files_alpm()?;
+    let pkg = alpm
+        .syncdbs()
+        .iter()
+        .find_map(|db| db.pkg(name).ok())
+        .ok_or_else(|| format!("no file list for {}, try pacman -Fy", name))?;
+    Ok(PackageFiles {
+        name: name.to_string(),
+        files: file_names(pkg),
+        installed: false,
+    })
+}
+
+fn file_names(pkg: &alpm::Package) -> Vec<String> {
+    pkg.files()
+        .files()
+        .iter()
+        .map(|f| format!("/{}", f.name()))
+        .collect()
+}
+
+// a missing log is an empty history
+pub fn load_history(name: &str) -> Result<PackageHistory, Box<dyn Error>> {
+    let log = match fs::read(PACMAN_LOG) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(PackageHistory {
+        name: name.to_string(),
+        entries: parse_history(&log, name),
+    })
+}
+
+// picks the transactions of `name` out of pacman.log lines like
+// `[2024-03-01T10:15:12+0100] [ALPM] upgraded linux (6.7.6-1 -> 6.7.7-1)`
+pub fn parse_history(log: &str, name: &str) -> Vec<HistoryEntry> {
+    log.lines()
+        .filter_map(|line| {
+            let (date, rest) = line.strip_prefix('[')?.split_once("] [ALPM] ")?;
+            let (action, rest) = rest.split_once(' ')?;
+            let (pkg, version) = rest.split_once(' ')?;
+            let version = version.strip_prefix('(')?.strip_suffix(')')?;
+            let known = matches!(
+                action,
+                "installed" | "upgraded" | "downgraded" | "reinstalled" | "removed"
+            );
+            (known && pkg == name).then(|| HistoryEntry {
+                date: date.get(..16).unwrap_or(date).replacen('T', " ", 1),
+                action: action.to_string(),
+                version: version.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+
+use crate::{
+    backend::{aur::AurClient, open_alpm, open_files_alpm, xdg},
+    objects::fulltext::{Doc, FullTextIndex, Sources},
+};
+
+const SYNC_DIR: &str = "/var/lib/pacman/sync";
+const AUR_METADATA: &str = "packages-meta-ext-v1.json.gz";
+// aurweb regenerates the dump every few minutes, once a day is plenty here
+const AUR_METADATA_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const AUR_METADATA_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Deserialize)]
+struct AurMetadata {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Version")]
+    version: String,
+    #[serde(rename = "Description")]
+    description: Option<String>,
+    #[serde(rename = "Provides")]
+    provides: Option<Vec<String>>,
+    #[serde(rename = "NumVotes")]
+    num_votes: Option<u64>,
+    #[serde(rename = "Popularity")]
+    popularity: Option<f64>,
+}
+
+pub fn index_path() -> PathBuf {
+    xdg::cache_dir().join("fulltext.json")
+}
+
+fn metadata_path() -> PathBuf {
+    xdg::cache_dir().join(AUR_METADATA)
+}
+
+// refreshes the aur metadata dump when it is old, then loads the index from
+// the cache unless a sync db or the dump changed since it was built. a failed
+// refresh comes along with the index
+pub async fn open_index(
+    client: &AurClient,
+) -> Result<(FullTextIndex, Option<String>), Box<dyn Error>> {
+    let metadata = metadata_path();
+    // without a dump the repos can still be searched, an old dump still
+    // covers the aur
+    let refresh_error = refresh_aur_metadata(client, &metadata)
+        .await
+        .err()
+        .map(|e| e.to_string());
+    let index = tokio::task::spawn_blocking(move || load_or_build(&index_path(), &metadata))
+        .await?
+        .map_err(|e| format!("full-text index: {}", e))?;
+    Ok((index, refresh_error))
+}
+
+async fn refresh_aur_metadata(client: &AurClient, path: &Path) -> Result<(), Box<dyn Error>> {
+    let fresh = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < AUR_METADATA_AGE);
+    if fresh {
+        return Ok(());
+    }
+    let bytes = client
+        .get_bytes(&format!("/{}", AUR_METADATA), AUR_METADATA_TIMEOUT)
+        .await?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+pub fn load_or_build(path: &Path, metadata: &Path) -> Result<FullTextIndex, String> {
+    let sources = current_sources(metadata);
+    let cached = fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<FullTextIndex>(&bytes).ok())
+        .filter(|index| index.sources == sources);
+    if let Some(index) = cached {
+        return Ok(index);
+    }
+
+    let mut docs = repo_docs().map_err(|e| e.to_string())?;
+    if metadata.is_file() {
+        docs.extend(aur_docs(metadata).map_err(|e| e.to_string())?);
+    }
+    let index = FullTextIndex::build(docs, sources);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let bytes = serde_json::to_vec(&index).map_err(|e| e.to_string())?;
+    fs::write(path, bytes).map_err(|e| e.to_string())?;
+    Ok(index)
+}
+
+// the sync and files dbs plus the aur dump, with their mtimes
+fn current_sources(metadata: &Path) -> Sources {
+    let mut paths: Vec<PathBuf> = fs::read_dir(SYNC_DIR)
+        .map(|dir| {
+            dir.filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.extension()
+                        .is_some_and(|ext| ext == "db" || ext == "files")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths.push(metadata.to_path_buf());
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some((path.display().to_string(), secs))
+        })
+        .collect()
+}
+
+fn repo_docs() -> Result<Vec<Doc>, Box<dyn Error>> {
+    // the .files dbs only exist after `pacman -Fy`, without them the
+    // packages are indexed without files
+    let mut files: HashMap<(String, String), Vec<String>> = HashMap::new();
+    if let Ok(alpm) = open_files_alpm() {
+        for db in alpm.syncdbs() {
+            for pkg in db.pkgs() {
+                let paths = pkg
+                    .files()
+                    .files()
+                    .iter()
+                    .map(|f| f.name().to_string())
+                    .filter(|f| indexed_file(f))
+                    .collect();
+                files.insert((db.name().to_string(), pkg.name().to_string()), paths);
+            }
+        }
+    }
+
+    let alpm = open_alpm()?;
+    let mut docs = Vec::new();
+    for db in alpm.syncdbs() {
+        for pkg in db.pkgs() {
+            let key = (db.name().to_string(), pkg.name().to_string());
+            docs.push(Doc {
+                name: pkg.name().to_string(),
+                repo: db.name().to_string(),
+                version: pkg.version().to_string(),
+                description: pkg.desc().unwrap_or_default().to_string(),
+                provides: pkg
+                    .provides()
+                    .iter()
+                    .map(|d| d.name().to_string())
+                    .collect(),
+                files: files.remove(&key).unwrap_or_default(),
+                votes: None,
+                popularity: None,
+            });
+        }
+    }
+    Ok(docs)
+}
+
+// every file of every package would make the index huge, the ones people
+// look for are executables, libraries, desktop entries and services
+pub fn indexed_file(path: &str) -> bool {
+    if path.ends_with('/') {
+        return false;
+    }
+    let in_dir = |dir: &str| {
+        path.strip_prefix(dir)
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains('/'))
+    };
+    in_dir("usr/bin/")
+        || (in_dir("usr/lib/") && path.contains(".so"))
+        || (in_dir("usr/share/applications/") && path.ends_with(".desktop"))
+        || in_dir("usr/lib/systemd/system/")
+        || in_dir("usr/lib/systemd/user/")
+}
+
+fn aur_docs(path: &Path) -> Result<Vec<Doc>, Box<dyn Error>> {
+    let mut json = Vec::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_end(&mut json)?;
+    let packages: Vec<AurMetadata> = serde_json::from_slice(&json)?;
+    Ok(packages
+        .into_iter()
+        .map(|p| Doc {
+            name: p.name,
+            repo: "aur".to_string(),
+            version: p.version,
+            description: p.description.unwrap_or_default(),
+            // versioned provides like `foo=1.2` are indexed by name
+            provides: p
+                .provides
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|p| p.split(['=', '<', '>']).next())
+                .map(str::to_string)
+                .collect(),
+            files: Vec::new(),
+            votes: p.num_votes,
+            popularity: p.popularity,
+        })
+        .collect())
+}
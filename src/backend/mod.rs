@@ -1,19 +1,37 @@
-use alpm::{Alpm, SigLevel};
-use std::{collections::HashSet, error::Error, io, process::Command};
+use alpm::{Alpm, PackageReason, SigLevel};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    io::{self, Write},
+    process::Command,
+};
 
-use crate::objects::stat::{App, ItemRepo, Package};
+use crate::objects::stat::{App, ItemRepo, Notice, Package};
+use crate::ui::{edit::set_bracketed_paste, mouse::set_mouse_capture};
 pub mod aur;
+pub mod comments;
+pub mod details;
+pub mod fulltext;
+pub mod pkgbuild;
+pub mod updates;
+pub mod xdg;
 
-pub fn load_repo_packages() -> Result<Vec<Package>, Box<dyn Error>> {
+fn open_alpm() -> Result<Alpm, Box<dyn Error>> {
     let alpm = Alpm::new("/", "/var/lib/pacman")?;
+    register_repos(&alpm);
+    Ok(alpm)
+}
 
-    let mut packages = Vec::new();
-    let installed: HashSet<String> = alpm
-        .localdb()
-        .pkgs()
-        .iter()
-        .map(|p| p.name().to_string())
-        .collect();
+// the same repos through their .files dbs, which also carry the file lists
+fn open_files_alpm() -> Result<Alpm, Box<dyn Error>> {
+    let mut alpm = Alpm::new("/", "/var/lib/pacman")?;
+    alpm.set_dbext(".files");
+    register_repos(&alpm);
+    Ok(alpm)
+}
+
+fn register_repos(alpm: &Alpm) {
     alpm.register_syncdb("core", SigLevel::USE_DEFAULT).unwrap();
     alpm.register_syncdb("extra", SigLevel::USE_DEFAULT)
         .unwrap();
@@ -23,18 +41,63 @@ pub fn load_repo_packages() -> Result<Vec<Package>, Box<dyn Error>> {
         .unwrap();
     alpm.register_syncdb("arch4edu", SigLevel::USE_DEFAULT)
         .unwrap();
+}
+
+// what the local db knows about an installed package
+struct LocalPackage {
+    version: String,
+    install_date: Option<i64>,
+    explicit: bool,
+    // installed as a dependency that nothing requires anymore, like pacman -Qdt
+    orphan: bool,
+}
+
+pub fn load_repo_packages() -> Result<Vec<Package>, Box<dyn Error>> {
+    let alpm = open_alpm()?;
+
+    let mut packages = Vec::new();
+    let installed: HashMap<String, LocalPackage> = alpm
+        .localdb()
+        .pkgs()
+        .iter()
+        .map(|p| {
+            let explicit = matches!(p.reason(), PackageReason::Explicit);
+            let local = LocalPackage {
+                version: p.version().to_string(),
+                install_date: p.install_date(),
+                explicit,
+                orphan: !explicit && p.required_by().is_empty() && p.optional_for().is_empty(),
+            };
+            (p.name().to_string(), local)
+        })
+        .collect();
 
     for repo in alpm.syncdbs() {
         let repo_name = repo.name();
         for pkg in repo.pkgs() {
-            let pack = Package::new(
-                installed.contains(pkg.name()),
+            let mut pack = Package::new(
+                installed.contains_key(pkg.name()),
                 ItemRepo::from(repo_name),
                 pkg.size() as u64 / 1024,
                 pkg.desc().unwrap_or("None").to_string(),
                 pkg.name().to_string(),
                 pkg.version().to_string(),
             );
+            pack.provides = pkg
+                .provides()
+                .iter()
+                .map(|d| d.name().to_string())
+                .collect();
+            pack.installed_size = pkg.isize() as u64 / 1024;
+            pack.build_date = Some(pkg.build_date());
+            if let Some(local) = installed.get(pkg.name()) {
+                pack.install_date = local.install_date;
+                pack.explicit = local.explicit;
+                pack.orphan = local.orphan;
+                pack.upgradable = alpm::vercmp(pkg.version().as_str(), local.version.as_str())
+                    == Ordering::Greater;
+                pack.installed_version = Some(local.version.clone());
+            }
 
             packages.push(pack);
         }
@@ -46,33 +109,158 @@ pub fn load_repo_packages() -> Result<Vec<Package>, Box<dyn Error>> {
     Ok(packages)
 }
 
+// installed packages that no registered sync db provides, as (name, version)
+pub fn load_foreign_packages() -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let alpm = open_alpm()?;
+    let synced: HashSet<String> = alpm
+        .syncdbs()
+        .iter()
+        .flat_map(|db| {
+            db.pkgs()
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    Ok(alpm
+        .localdb()
+        .pkgs()
+        .iter()
+        .filter(|p| !synced.contains(p.name()))
+        .map(|p| (p.name().to_string(), p.version().to_string()))
+        .collect())
+}
+
+// hands the terminal to a helper like paru until it exits, the caller
+// takes it back with resume_after_helper
+fn run_in_terminal(program: &str, args: &[&str]) -> bool {
+    set_mouse_capture(false);
+    set_bracketed_paste(false);
+    ratatui::restore();
+    Command::new(program)
+        .args(args)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+// keeps the helper's output on screen until Enter, the terminal is still in
+// its normal mode so this goes straight to stdout
+pub fn wait_for_enter() {
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "\nPress Enter to return to pacseen ");
+    let _ = stdout.flush();
+    io::stdin()
+        .read_line(&mut String::new())
+        .unwrap_or_default();
+}
+
 impl App {
     pub fn install_pack(&mut self, index: usize) {
-        let pack = &mut self.filtered[index];
-        let cmd = "paru".to_string();
-        if pack.is_installed {
-            ratatui::restore();
-            let mut child = Command::new(&cmd)
-                .arg("-Rns")
-                .arg(&pack.name)
-                .spawn()
-                .expect("failed to start a new process");
-            let status = child.wait().unwrap();
-            if status.success() {
-                pack.is_installed == false;
-            }
+        let source = self.filtered[index].source;
+        let pack = self.package_mut(source);
+        if run_in_terminal("paru", &["-S", &pack.name]) {
+            pack.is_installed = true;
+        }
+    }
 
-            return;
+    pub fn remove_pack(&mut self, name: &str) {
+        if run_in_terminal("paru", &["-Rns", name]) {
+            self.set_installed(&[name.to_string()], false);
         }
-        ratatui::restore();
-        let mut child = Command::new(&cmd)
-            .arg("-S")
-            .arg(&pack.name)
-            .spawn()
-            .expect("failed to start a new process");
-        let status = child.wait().unwrap_or_default();
-        if status.success() {
-            pack.is_installed = true;
+    }
+
+    // the marked packages that are not installed yet, whether paru ran
+    pub fn install_marked(&mut self) -> bool {
+        let names = self.marked_packages(false);
+        if names.is_empty() {
+            self.notice = Notice::warning("all marked packages are installed");
+            return false;
+        }
+        let mut args = vec!["-S"];
+        args.extend(names.iter().map(String::as_str));
+        if run_in_terminal("paru", &args) {
+            self.set_installed(&names, true);
+            self.marked.clear();
+            self.notice = Notice::info(format!("{} packages installed", names.len()));
+        }
+        true
+    }
+
+    pub fn remove_marked(&mut self) -> bool {
+        let names = self.marked_packages(true);
+        if names.is_empty() {
+            self.notice = Notice::warning("none of the marked packages is installed");
+            return false;
+        }
+        let mut args = vec!["-Rns"];
+        args.extend(names.iter().map(String::as_str));
+        if run_in_terminal("paru", &args) {
+            self.set_installed(&names, false);
+            self.marked.clear();
+            self.notice = Notice::info(format!("{} packages removed", names.len()));
+        }
+        true
+    }
+
+    // the marked packages that are, or are not, installed, sorted by name
+    pub fn marked_packages(&self, installed: bool) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .marked
+            .iter()
+            .filter(|name| self.is_installed(name) == installed)
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn is_installed(&self, name: &str) -> bool {
+        let mut packages = self
+            .items
+            .iter()
+            .chain(self.aur_results.iter())
+            .chain(self.text_packages.iter());
+        packages.any(|p| p.name == name && p.is_installed)
+    }
+
+    fn set_installed(&mut self, names: &[String], installed: bool) {
+        // the installed state takes part in matching
+        self.narrowed = None;
+        let packages = self
+            .items
+            .iter_mut()
+            .chain(self.aur_results.iter_mut())
+            .chain(self.text_packages.iter_mut());
+        for pack in packages.filter(|p| names.contains(&p.name)) {
+            pack.is_installed = installed;
+        }
+    }
+
+    // through paccache, which removes without asking, or paru, which asks
+    // before removing anything
+    pub fn clean_cache(&mut self, program: &str, args: &[&str]) {
+        if run_in_terminal(program, args) {
+            self.notice = Notice::info("package cache cleaned");
+        }
+    }
+
+    // whether paru ran, the terminal then has to be resumed
+    pub fn upgrade_aur_packages(&mut self) -> bool {
+        let Some(report) = &mut self.aur_updates else {
+            return false;
+        };
+        if report.updates.is_empty() {
+            self.notice = Notice::warning("no aur updates to install");
+            return false;
+        }
+        let mut args = vec!["-S"];
+        args.extend(report.updates.iter().map(|u| u.name.as_str()));
+        if run_in_terminal("paru", &args) {
+            self.notice = Notice::info(format!("{} aur packages upgraded", report.updates.len()));
+            report.updates.clear();
         }
+        true
     }
 }
@@ -0,0 +1,136 @@
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::backend::{aur::AurClient, xdg};
+
+#[derive(Debug, Clone)]
+pub struct AurSource {
+    pub name: String,
+    pub dir: PathBuf,
+    pub pkgbuild: String,
+    pub files: Vec<String>,
+}
+
+pub fn cache_dir() -> PathBuf {
+    xdg::cache_dir().join("aur")
+}
+
+// clones (or pulls) the AUR git repo of `name` into `cache`, falling back to
+// downloading PKGBUILD, .SRCINFO and the install files when git is unavailable
+pub async fn fetch_aur_source(
+    client: &AurClient,
+    name: String,
+    cache: &Path,
+) -> Result<AurSource, Box<dyn Error>> {
+    // git_sync removes the directory, so the name must not point outside the cache
+    if !is_plain_name(&name) {
+        return Err(format!("{:?} is not a package name", name).into());
+    }
+    let dir = cache.join(&name);
+    let synced = {
+        let (url, dir) = (client.git_url(&name), dir.clone());
+        tokio::task::spawn_blocking(move || git_sync(&url, &dir)).await?
+    };
+    if !synced {
+        download_plain(client, &name, &dir).await?;
+    }
+    read_source(name, dir)
+}
+
+pub fn is_plain_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+fn git_sync(url: &str, dir: &Path) -> bool {
+    let status = if dir.join(".git").is_dir() {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["pull", "--ff-only", "--quiet"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+    } else {
+        // a previous plain download may have left files behind
+        let _ = fs::remove_dir_all(dir);
+        Command::new("git")
+            .args(["clone", "--depth", "1", "--quiet"])
+            .arg(url)
+            .arg(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+    };
+    status.map(|s| s.success()).unwrap_or(false) && dir.join("PKGBUILD").is_file()
+}
+
+async fn download_plain(client: &AurClient, name: &str, dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+
+    let pkgbuild = fetch_plain(client, name, "PKGBUILD").await?;
+    fs::write(dir.join("PKGBUILD"), pkgbuild)?;
+
+    let srcinfo = fetch_plain(client, name, ".SRCINFO").await?;
+    fs::write(dir.join(".SRCINFO"), &srcinfo)?;
+
+    for file in install_files(&srcinfo) {
+        let content = fetch_plain(client, name, &file).await?;
+        fs::write(dir.join(&file), content)?;
+    }
+    Ok(())
+}
+
+async fn fetch_plain(client: &AurClient, name: &str, file: &str) -> Result<String, Box<dyn Error>> {
+    client
+        .get_text(&format!("/cgit/aur.git/plain/{}?h={}", file, name))
+        .await
+}
+
+// `install = foo.install` entries of a .SRCINFO, restricted to plain file names
+fn install_files(srcinfo: &str) -> Vec<String> {
+    let mut files: Vec<String> = srcinfo
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("install = "))
+        .filter_map(|file| Path::new(file.trim()).file_name())
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn read_source(name: String, dir: PathBuf) -> Result<AurSource, Box<dyn Error>> {
+    let pkgbuild = fs::read_to_string(dir.join("PKGBUILD"))
+        .map_err(|e| format!("{} has no readable PKGBUILD: {}", name, e))?;
+    let mut files = Vec::new();
+    list_files(&dir, &dir, &mut files)?;
+    files.sort();
+    Ok(AurSource {
+        name,
+        dir,
+        pkgbuild,
+        files,
+    })
+}
+
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.file_name().is_some_and(|n| n == ".git") {
+            continue;
+        }
+        if path.is_dir() {
+            list_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.display().to_string());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::{cmp::Ordering, collections::HashMap, error::Error};
+
+use crate::backend::{
+    aur::{AurClient, AurRaw},
+    load_foreign_packages,
+};
+
+#[derive(Debug, Clone)]
+pub struct AurUpdate {
+    pub name: String,
+    pub local_version: String,
+    pub aur_version: String,
+    pub out_of_date: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateReport {
+    pub updates: Vec<AurUpdate>,
+    pub flagged: Vec<AurUpdate>,
+    pub missing: Vec<String>,
+    pub checked: usize,
+}
+
+pub async fn check_aur_updates(client: &AurClient) -> Result<UpdateReport, Box<dyn Error>> {
+    let foreign = load_foreign_packages()?;
+    let names: Vec<String> = foreign.iter().map(|(name, _)| name.clone()).collect();
+    let aur = client.info(&names).await?;
+    Ok(build_report(foreign, aur))
+}
+
+pub fn build_report(foreign: Vec<(String, String)>, aur: Vec<AurRaw>) -> UpdateReport {
+    let mut aur: HashMap<String, AurRaw> = aur.into_iter().map(|p| (p.name.clone(), p)).collect();
+    let mut report = UpdateReport {
+        checked: foreign.len(),
+        ..Default::default()
+    };
+
+    for (name, local_version) in foreign {
+        let Some(remote) = aur.remove(&name) else {
+            report.missing.push(name);
+            continue;
+        };
+        let update = AurUpdate {
+            out_of_date: remote.out_of_date.is_some(),
+            aur_version: remote.version,
+            name,
+            local_version,
+        };
+        if update.out_of_date {
+            report.flagged.push(update.clone());
+        }
+        if alpm::vercmp(update.aur_version.as_str(), update.local_version.as_str())
+            == Ordering::Greater
+        {
+            report.updates.push(update);
+        }
+    }
+
+    report.updates.sort_by(|a, b| a.name.cmp(&b.name));
+    report.flagged.sort_by(|a, b| a.name.cmp(&b.name));
+    report.missing.sort();
+    report
+}
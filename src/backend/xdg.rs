@@ -0,0 +1,22 @@
+use std::{env, path::PathBuf};
+
+fn base_dir(var: &str, fallback: &str) -> PathBuf {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+        .unwrap_or_else(env::temp_dir)
+        .join("pacseen")
+}
+
+pub fn config_dir() -> PathBuf {
+    base_dir("XDG_CONFIG_HOME", ".config")
+}
+
+pub fn cache_dir() -> PathBuf {
+    base_dir("XDG_CACHE_HOME", ".cache")
+}
+
+pub fn state_dir() -> PathBuf {
+    base_dir("XDG_STATE_HOME", ".local/state")
+}
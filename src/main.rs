@@ -9,8 +9,8 @@ use tokio::select;
 use tokio::time::Duration;
 use tokio::time::sleep;
 
-use crate::objects::stat::App;
-use crate::ui::render;
+use crate::objects::stat::{App, InfoTab, Notice};
+use crate::ui::{edit::set_bracketed_paste, mouse::set_mouse_capture, render};
 
 pub mod backend;
 pub mod objects;
@@ -24,8 +24,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let terminal = ratatui::init();
     let mut state = App::new()?;
+    set_mouse_capture(state.config.mouse);
+    set_bracketed_paste(true);
     run(terminal, &mut state).await?;
 
+    set_mouse_capture(false);
+    set_bracketed_paste(false);
     ratatui::restore();
     result::Result::Ok(())
 }
@@ -33,23 +37,80 @@ async fn main() -> Result<(), Box<dyn Error>> {
 pub async fn run(mut terminal: DefaultTerminal, app: &mut App) -> Result<()> {
     loop {
         select! {
-            Some(pkgs) = app.aur_rx.recv() => {
-                if pkgs.is_empty(){
-                    app.notice = "aur searching error or the result is totally empty".to_string();
+            Some(results) = app.aur_rx.recv() => {
+                app.apply_aur_results(results);
+            }
+            Some(source) = app.source_rx.recv() => {
+                if let Some(source) = app.take_loaded(InfoTab::Pkgbuild, source, |s| &s.name) {
+                    app.notice = Notice::info(format!("{} files fetched for {}", source.files.len(), source.name));
+                    app.aur_source = Some(source);
+                }
+            }
+            Some(report) = app.updates_rx.recv() => {
+                match report {
+                    result::Result::Ok(report) => {
+                        app.notice = Notice::info(format!(
+                            "{} foreign packages checked: {} updates, {} flagged, {} not in the aur",
+                            report.checked,
+                            report.updates.len(),
+                            report.flagged.len(),
+                            report.missing.len()
+                        ));
+                        app.aur_updates = Some(report);
+                        app.mark_local_aur_packages();
+                        app.merge_results();
+                    }
+                    Err(e) => app.notice = Notice::error(e),
+                }
+            }
+            Some(index) = app.fulltext_rx.recv() => {
+                match index {
+                    result::Result::Ok((index, refresh_error)) => {
+                        app.apply_fulltext_index(index, refresh_error)
+                    }
+                    Err(e) => {
+                        app.fulltext_task = None;
+                        app.notice = Notice::error(e);
+                    }
+                }
+            }
+            Some(deps) = app.deps_rx.recv() => {
+                if let Some(deps) = app.take_loaded(InfoTab::Dependencies, deps, |d| &d.name) {
+                    app.package_deps = Some(deps);
+                }
+            }
+            Some(files) = app.files_rx.recv() => {
+                if let Some(files) = app.take_loaded(InfoTab::Files, files, |f| &f.name) {
+                    app.package_files = Some(files);
+                }
+            }
+            Some(history) = app.history_rx.recv() => {
+                if let Some(history) = app.take_loaded(InfoTab::History, history, |h| &h.name) {
+                    app.package_history = Some(history);
+                }
+            }
+            Some(comments) = app.comments_rx.recv() => {
+                if let Some(comments) = app.take_loaded(InfoTab::Comments, comments, |c| &c.name) {
+                    app.aur_comments = Some(comments);
                 }
-                app.filtered.extend(pkgs);
-                app.aur_search_block = false;
             }
             _ = sleep(Duration::from_millis(5)) => {
                 // UI tick
+                    if app.redraw {
+                        app.redraw = false;
+                        terminal.clear()?;
+                    }
                     terminal.draw(|frame| {
                         render(frame, app);
                     })?;
-                    if let Event::Key(key) = event::read()? {
-                        app.handle_key(key);
-                        if app.exit {
-                            break;
-                        }
+                    match event::read()? {
+                        Event::Key(key) => app.handle_key(key),
+                        Event::Mouse(mouse) => app.handle_mouse(mouse),
+                        Event::Paste(text) => app.paste(&text),
+                        _ => {}
+                    }
+                    if app.exit {
+                        break;
                     }
             }
         }
@@ -0,0 +1,118 @@
+use serde::Deserialize;
+
+// a column of the package table, named in kebab case in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    // the installed and marked markers
+    Installed,
+    Name,
+    Version,
+    InstalledVersion,
+    Repo,
+    Size,
+    Votes,
+    Popularity,
+    Updated,
+}
+
+impl Column {
+    pub const ALL: [Column; 9] = [
+        Column::Installed,
+        Column::Name,
+        Column::Version,
+        Column::InstalledVersion,
+        Column::Repo,
+        Column::Size,
+        Column::Votes,
+        Column::Popularity,
+        Column::Updated,
+    ];
+
+    pub const DEFAULT: [Column; 4] = [
+        Column::Installed,
+        Column::Name,
+        Column::Version,
+        Column::Repo,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Installed => "",
+            Column::Name => "Name",
+            Column::Version => "Version",
+            Column::InstalledVersion => "Installed",
+            Column::Repo => "Repo",
+            Column::Size => "Size",
+            Column::Votes => "Votes",
+            Column::Popularity => "Pop",
+            Column::Updated => "Updated",
+        }
+    }
+
+    // for the column popup, where the installed column has no title
+    pub fn label(&self) -> &'static str {
+        match self {
+            Column::Installed => "installed marker",
+            Column::InstalledVersion => "installed version",
+            Column::Popularity => "popularity",
+            Column::Updated => "last updated",
+            column => column.title(),
+        }
+    }
+
+    // numbers line up on the right
+    pub fn numeric(&self) -> bool {
+        matches!(self, Column::Size | Column::Votes | Column::Popularity)
+    }
+}
+
+// every column in display order, the configured ones first, and which of
+// them are shown
+#[derive(Debug, Clone)]
+pub struct Columns {
+    pub order: Vec<Column>,
+    shown: Vec<Column>,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self::new(&Column::DEFAULT)
+    }
+}
+
+impl Columns {
+    pub fn new(configured: &[Column]) -> Self {
+        let mut order: Vec<Column> = Vec::new();
+        for column in configured.iter().chain(Column::ALL.iter()) {
+            if !order.contains(column) {
+                order.push(*column);
+            }
+        }
+        Self {
+            order,
+            shown: configured.to_vec(),
+        }
+    }
+
+    pub fn is_shown(&self, column: Column) -> bool {
+        self.shown.contains(&column)
+    }
+
+    pub fn shown(&self) -> Vec<Column> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|c| self.is_shown(*c))
+            .collect()
+    }
+
+    // the last shown column stays
+    pub fn toggle(&mut self, column: Column) {
+        match self.is_shown(column) {
+            true if self.shown.len() > 1 => self.shown.retain(|c| *c != column),
+            true => {}
+            false => self.shown.push(column),
+        }
+    }
+}
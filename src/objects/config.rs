@@ -0,0 +1,69 @@
+use std::{env, error::Error, fs, io, path::PathBuf, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    backend::{
+        aur::{AurClient, DEFAULT_AUR_URL},
+        xdg,
+    },
+    objects::{columns::Column, keymap::KeyConfig, layout::LayoutConfig, theme::ThemeConfig},
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub aur_url: String,
+    pub aur_timeout: u64, // seconds
+    pub history_size: usize,
+    // group the list into repo and aur sections
+    pub section_headers: bool,
+    // capture the mouse, terminals only select text with shift held then
+    pub mouse: bool,
+    // the columns of the package table, in order
+    pub columns: Vec<Column>,
+    pub layout: LayoutConfig,
+    pub keys: KeyConfig,
+    pub theme: ThemeConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aur_url: DEFAULT_AUR_URL.to_string(),
+            aur_timeout: 10,
+            history_size: 500,
+            section_headers: false,
+            mouse: true,
+            columns: Column::DEFAULT.to_vec(),
+            layout: LayoutConfig::default(),
+            keys: KeyConfig::default(),
+            theme: ThemeConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        xdg::config_dir().join("config.toml")
+    }
+
+    // a missing config file is not an error, PACSEEN_AUR_URL overrides the file
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let path = Self::path();
+        let mut config: Config = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| format!("invalid config {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Ok(url) = env::var("PACSEEN_AUR_URL") {
+            config.aur_url = url;
+        }
+        Ok(config)
+    }
+
+    pub fn aur_client(&self) -> AurClient {
+        AurClient::new(&self.aur_url, Duration::from_secs(self.aur_timeout))
+    }
+}
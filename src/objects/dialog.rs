@@ -0,0 +1,157 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogKind {
+    // focused on no until yes is chosen
+    Confirm { yes: bool },
+    Input { text: String, cursor: usize },
+    Pick { options: Vec<String>, cursor: usize },
+}
+
+// what the answer of a dialog goes to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Purpose {
+    RemovePackage(String),
+    // what to do with the marked packages
+    Batch,
+    RemoveMarked,
+    // which part of the package cache to clean
+    CleanCache,
+    KeepVersions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Yes,
+    Text(String),
+    Picked(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogEvent {
+    Open,
+    Cancelled,
+    Answered(Answer),
+}
+
+// a modal box over the layout that takes every key until it is answered
+// or dismissed
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    pub title: String,
+    pub message: String,
+    pub kind: DialogKind,
+    pub purpose: Purpose,
+    // why the last answer was not taken, e.g. an input that is no number
+    pub error: Option<String>,
+}
+
+impl Dialog {
+    pub fn confirm(title: &str, message: String, purpose: Purpose) -> Self {
+        Self::new(title, message, DialogKind::Confirm { yes: false }, purpose)
+    }
+
+    pub fn input(title: &str, message: String, text: &str, purpose: Purpose) -> Self {
+        let kind = DialogKind::Input {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+        };
+        Self::new(title, message, kind, purpose)
+    }
+
+    pub fn pick(title: &str, message: String, options: Vec<String>, purpose: Purpose) -> Self {
+        let kind = DialogKind::Pick { options, cursor: 0 };
+        Self::new(title, message, kind, purpose)
+    }
+
+    fn new(title: &str, message: String, kind: DialogKind, purpose: Purpose) -> Self {
+        Self {
+            title: title.to_string(),
+            message,
+            kind,
+            purpose,
+            error: None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogEvent {
+        if key.code == KeyCode::Esc {
+            return DialogEvent::Cancelled;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match &mut self.kind {
+            DialogKind::Confirm { yes } => match key.code {
+                KeyCode::Char('y') => return DialogEvent::Answered(Answer::Yes),
+                KeyCode::Char('n') | KeyCode::Char('q') => return DialogEvent::Cancelled,
+                KeyCode::Enter if *yes => return DialogEvent::Answered(Answer::Yes),
+                KeyCode::Enter => return DialogEvent::Cancelled,
+                KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Tab
+                | KeyCode::BackTab
+                | KeyCode::Char('h')
+                | KeyCode::Char('l') => *yes = !*yes,
+                _ => {}
+            },
+            DialogKind::Input { text, cursor } => {
+                let len = text.chars().count();
+                let byte = |i: usize| text.char_indices().nth(i).map_or(text.len(), |(b, _)| b);
+                match key.code {
+                    KeyCode::Enter => return DialogEvent::Answered(Answer::Text(text.clone())),
+                    KeyCode::Char('u') if ctrl => {
+                        text.replace_range(..byte(*cursor), "");
+                        *cursor = 0;
+                    }
+                    KeyCode::Char(c) if !ctrl => {
+                        text.insert(byte(*cursor), c);
+                        *cursor += 1;
+                    }
+                    KeyCode::Backspace if *cursor > 0 => {
+                        *cursor -= 1;
+                        text.remove(byte(*cursor));
+                    }
+                    KeyCode::Delete if *cursor < len => {
+                        text.remove(byte(*cursor));
+                    }
+                    KeyCode::Left => *cursor = cursor.saturating_sub(1),
+                    KeyCode::Right => *cursor = (*cursor + 1).min(len),
+                    KeyCode::Home => *cursor = 0,
+                    KeyCode::End => *cursor = len,
+                    _ => {}
+                }
+            }
+            DialogKind::Pick { options, cursor } => {
+                let last = options.len().saturating_sub(1);
+                match key.code {
+                    KeyCode::Enter | KeyCode::Char('l') if !options.is_empty() => {
+                        return DialogEvent::Answered(Answer::Picked(*cursor));
+                    }
+                    KeyCode::Char('q') => return DialogEvent::Cancelled,
+                    KeyCode::Char('j') | KeyCode::Down => *cursor = (*cursor + 1).min(last),
+                    KeyCode::Char('k') | KeyCode::Up => *cursor = cursor.saturating_sub(1),
+                    // the options are numbered from 1
+                    KeyCode::Char(c) => {
+                        let picked = c.to_digit(10).map(|d| d as usize);
+                        if let Some(i) = picked.filter(|d| (1..=options.len()).contains(d)) {
+                            return DialogEvent::Answered(Answer::Picked(i - 1));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        DialogEvent::Open
+    }
+
+    // only an input dialog takes pasted text, at its cursor
+    pub fn paste(&mut self, pasted: &str) {
+        if let DialogKind::Input { text, cursor } = &mut self.kind {
+            let byte = text
+                .char_indices()
+                .nth(*cursor)
+                .map_or(text.len(), |(b, _)| b);
+            text.insert_str(byte, pasted);
+            *cursor += pasted.chars().count();
+        }
+    }
+}
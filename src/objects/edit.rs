@@ -0,0 +1,95 @@
+// what an edit of the search box did, a run of the same kind of edit is
+// undone in one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    Insert,
+    Delete,
+    Kill,
+    Paste,
+    // the whole search swapped for a history entry or a suggestion
+    Replace,
+}
+
+const MAX_UNDO: usize = 100;
+
+// the undo stack and kill buffer of the search box
+#[derive(Debug, Clone, Default)]
+pub struct Edits {
+    // the search and cursor from before each undo step
+    undo: Vec<(String, usize)>,
+    last: Option<EditKind>,
+    // the text the last Ctrl-W, Ctrl-U or Ctrl-K removed, for Ctrl-Y
+    pub killed: String,
+}
+
+impl Edits {
+    // remembers the state before an edit unless it continues the last one
+    pub fn record(&mut self, kind: EditKind, search: &str, cursor: usize) {
+        let continues = self.last == Some(kind)
+            && matches!(
+                kind,
+                EditKind::Insert | EditKind::Delete | EditKind::Replace
+            );
+        self.last = Some(kind);
+        if continues {
+            return;
+        }
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push((search.to_string(), cursor));
+    }
+
+    // a cursor motion ends the run of edits
+    pub fn break_run(&mut self) {
+        self.last = None;
+    }
+
+    pub fn undo(&mut self) -> Option<(String, usize)> {
+        self.last = None;
+        self.undo.pop()
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+// the start of the word before `cursor`, like readline's Alt-B
+pub fn word_left(text: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = cursor.min(chars.len());
+    while i > 0 && !is_word(chars[i - 1]) {
+        i -= 1;
+    }
+    while i > 0 && is_word(chars[i - 1]) {
+        i -= 1;
+    }
+    i
+}
+
+// the end of the word after `cursor`, like readline's Alt-F
+pub fn word_right(text: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = cursor.min(chars.len());
+    while i < chars.len() && !is_word(chars[i]) {
+        i += 1;
+    }
+    while i < chars.len() && is_word(chars[i]) {
+        i += 1;
+    }
+    i
+}
+
+// where Ctrl-W deletes back to, words end at whitespace there
+pub fn rubout_start(text: &str, cursor: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = cursor.min(chars.len());
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    while i > 0 && !chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    i
+}
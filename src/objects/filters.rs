@@ -0,0 +1,186 @@
+use std::{collections::BTreeSet, error::Error, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::xdg,
+    objects::stat::{ItemRepo, Package},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceFilter {
+    #[default]
+    All,
+    Repo,
+    Aur,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstalledFilter {
+    #[default]
+    All,
+    Installed,
+    NotInstalled,
+}
+
+// restrictions on the result list on top of the query, persisted in the
+// state dir. repos are stored as the hidden ones so new repos show up
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Filters {
+    pub source: SourceFilter,
+    pub hidden_repos: BTreeSet<String>,
+    pub installed: InstalledFilter,
+    pub upgradable: bool,
+    pub explicit: bool,
+    pub orphans: bool,
+}
+
+// a row of the filter popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterOption {
+    RepoOnly,
+    AurOnly,
+    Repo(String),
+    Installed,
+    NotInstalled,
+    Upgradable,
+    Explicit,
+    Orphans,
+}
+
+impl FilterOption {
+    // the popup rows for the repos present in the package list
+    pub fn all(repos: &[String]) -> Vec<FilterOption> {
+        let mut options = vec![FilterOption::RepoOnly, FilterOption::AurOnly];
+        options.extend(repos.iter().cloned().map(FilterOption::Repo));
+        options.extend([
+            FilterOption::Installed,
+            FilterOption::NotInstalled,
+            FilterOption::Upgradable,
+            FilterOption::Explicit,
+            FilterOption::Orphans,
+        ]);
+        options
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            FilterOption::RepoOnly => "repo packages only".to_string(),
+            FilterOption::AurOnly => "aur packages only".to_string(),
+            FilterOption::Repo(repo) => format!("repo {}", repo),
+            FilterOption::Installed => "installed".to_string(),
+            FilterOption::NotInstalled => "not installed".to_string(),
+            FilterOption::Upgradable => "upgradable".to_string(),
+            FilterOption::Explicit => "explicitly installed".to_string(),
+            FilterOption::Orphans => "orphans".to_string(),
+        }
+    }
+}
+
+impl Filters {
+    pub fn path() -> PathBuf {
+        xdg::state_dir().join("filters.toml")
+    }
+
+    // a missing or unreadable state file only resets the filters
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_checked(&self, option: &FilterOption) -> bool {
+        match option {
+            FilterOption::RepoOnly => self.source == SourceFilter::Repo,
+            FilterOption::AurOnly => self.source == SourceFilter::Aur,
+            FilterOption::Repo(repo) => !self.hidden_repos.contains(repo),
+            FilterOption::Installed => self.installed == InstalledFilter::Installed,
+            FilterOption::NotInstalled => self.installed == InstalledFilter::NotInstalled,
+            FilterOption::Upgradable => self.upgradable,
+            FilterOption::Explicit => self.explicit,
+            FilterOption::Orphans => self.orphans,
+        }
+    }
+
+    // repo only and aur only, installed and not installed exclude each other
+    pub fn toggle(&mut self, option: &FilterOption) {
+        let checked = self.is_checked(option);
+        match option {
+            FilterOption::RepoOnly | FilterOption::AurOnly if checked => {
+                self.source = SourceFilter::All
+            }
+            FilterOption::RepoOnly => self.source = SourceFilter::Repo,
+            FilterOption::AurOnly => self.source = SourceFilter::Aur,
+            FilterOption::Repo(repo) if checked => {
+                self.hidden_repos.insert(repo.clone());
+            }
+            FilterOption::Repo(repo) => {
+                self.hidden_repos.remove(repo);
+            }
+            FilterOption::Installed | FilterOption::NotInstalled if checked => {
+                self.installed = InstalledFilter::All
+            }
+            FilterOption::Installed => self.installed = InstalledFilter::Installed,
+            FilterOption::NotInstalled => self.installed = InstalledFilter::NotInstalled,
+            FilterOption::Upgradable => self.upgradable = !checked,
+            FilterOption::Explicit => self.explicit = !checked,
+            FilterOption::Orphans => self.orphans = !checked,
+        }
+    }
+
+    pub fn allows(&self, pkg: &Package) -> bool {
+        let aur = matches!(pkg.repo, ItemRepo::AUR(_));
+        let source = match self.source {
+            SourceFilter::All => true,
+            SourceFilter::Repo => !aur,
+            SourceFilter::Aur => aur,
+        };
+        let installed = match self.installed {
+            InstalledFilter::All => true,
+            InstalledFilter::Installed => pkg.is_installed,
+            InstalledFilter::NotInstalled => !pkg.is_installed,
+        };
+        source
+            && installed
+            && (aur || !self.hidden_repos.contains(pkg.repo.name()))
+            && (!self.upgradable || pkg.upgradable)
+            && (!self.explicit || pkg.explicit)
+            && (!self.orphans || pkg.orphan)
+    }
+
+    // short labels of the active filters, shown above the list
+    pub fn chips(&self) -> Vec<String> {
+        let mut chips = Vec::new();
+        match self.source {
+            SourceFilter::All => {}
+            SourceFilter::Repo => chips.push("repo".to_string()),
+            SourceFilter::Aur => chips.push("aur".to_string()),
+        }
+        chips.extend(self.hidden_repos.iter().map(|repo| format!("-{}", repo)));
+        match self.installed {
+            InstalledFilter::All => {}
+            InstalledFilter::Installed => chips.push("installed".to_string()),
+            InstalledFilter::NotInstalled => chips.push("not installed".to_string()),
+        }
+        let flags = [
+            (self.upgradable, "upgradable"),
+            (self.explicit, "explicit"),
+            (self.orphans, "orphans"),
+        ];
+        chips.extend(flags.iter().filter(|f| f.0).map(|f| f.1.to_string()));
+        chips
+    }
+}
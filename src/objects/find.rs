@@ -0,0 +1,34 @@
+// the `/` search over the names of the current results
+#[derive(Debug, Clone, Default)]
+pub struct ListFind {
+    pub pattern: String,
+    // the prompt is open and takes the typed keys
+    pub typing: bool,
+    // the selection from before the prompt opened, typing searches from it
+    // and Esc goes back to it
+    pub origin: Option<usize>,
+}
+
+// smart case: the pattern only cares about case once it has a capital
+fn contains(name: &str, pattern: &str) -> bool {
+    match pattern.chars().any(char::is_uppercase) {
+        true => name.contains(pattern),
+        false => name.to_lowercase().contains(pattern),
+    }
+}
+
+// the next row after `from` whose name contains the pattern, going around
+// the end of the list. `from` itself comes last
+pub fn find_row(names: &[&str], pattern: &str, from: usize, forward: bool) -> Option<usize> {
+    let len = names.len();
+    if pattern.is_empty() || len == 0 {
+        return None;
+    }
+    let from = from.min(len - 1);
+    (1..=len)
+        .map(|step| match forward {
+            true => (from + step) % len,
+            false => (from + len - step) % len,
+        })
+        .find(|&i| contains(names[i], pattern))
+}
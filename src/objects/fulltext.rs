@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+// ordered by weight, a token found in several fields counts for the best one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Field {
+    File,
+    Description,
+    Provides,
+    Name,
+}
+
+impl Field {
+    fn weight(self) -> f64 {
+        match self {
+            Field::Name => 4.0,
+            Field::Provides => 3.0,
+            Field::Description => 2.0,
+            Field::File => 1.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Description => "desc",
+            Field::Provides => "provides",
+            Field::File => "file",
+        }
+    }
+}
+
+// a package as the index knows it, from a sync db or the aur metadata
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Doc {
+    pub name: String,
+    pub repo: String,
+    pub version: String,
+    pub description: String,
+    pub provides: Vec<String>,
+    pub files: Vec<String>,
+    pub votes: Option<u64>,
+    pub popularity: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub field: Field,
+    // the provides name or file path that matched, the description or name otherwise
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextHit {
+    pub doc: usize,
+    pub score: f64,
+    pub matched: TextMatch,
+}
+
+// the files the index was built from with their mtimes, the index is stale
+// as soon as they differ
+pub type Sources = Vec<(String, u64)>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FullTextIndex {
+    pub sources: Sources,
+    pub docs: Vec<Doc>,
+    // token to the documents containing it, with the best field it is in
+    postings: BTreeMap<String, Vec<(u32, Field)>>,
+}
+
+pub const MAX_TEXT_HITS: usize = 200;
+
+// words that say nothing about a package, "which package has a pdf viewer"
+// searches for pdf and viewer
+const STOP_WORDS: [&str; 16] = [
+    "a", "an", "and", "for", "has", "in", "is", "of", "on", "or", "package", "ships", "that",
+    "the", "which", "with",
+];
+
+pub fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+impl FullTextIndex {
+    pub fn build(docs: Vec<Doc>, sources: Sources) -> Self {
+        let mut postings: BTreeMap<String, Vec<(u32, Field)>> = BTreeMap::new();
+        for (i, doc) in docs.iter().enumerate() {
+            let mut fields: HashMap<String, Field> = HashMap::new();
+            let mut add = |text: &str, field: Field| {
+                for token in tokens(text) {
+                    let best = fields.entry(token).or_insert(field);
+                    *best = (*best).max(field);
+                }
+            };
+            add(&doc.name, Field::Name);
+            doc.provides.iter().for_each(|p| add(p, Field::Provides));
+            add(&doc.description, Field::Description);
+            doc.files.iter().for_each(|f| add(f, Field::File));
+            for (token, field) in fields {
+                postings.entry(token).or_default().push((i as u32, field));
+            }
+        }
+        Self {
+            sources,
+            docs,
+            postings,
+        }
+    }
+
+    // documents matching more query words come first, then by a tf-idf like
+    // score weighted by field. words of three or more letters also match as
+    // prefixes, at half the weight
+    pub fn search(&self, query: &str) -> Vec<TextHit> {
+        let mut terms: Vec<String> = tokens(query)
+            .filter(|t| !STOP_WORDS.contains(&t.as_str()))
+            .collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let total = self.docs.len() as f64;
+        // doc -> (matched terms, score, best field)
+        let mut scores: HashMap<u32, (usize, f64, Field)> = HashMap::new();
+        for term in &terms {
+            let mut best: HashMap<u32, (f64, Field)> = HashMap::new();
+            let candidates = self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(token, _)| token.starts_with(term.as_str()))
+                .filter(|(token, _)| *token == term || term.chars().count() >= 3);
+            for (token, postings) in candidates {
+                let idf = (1.0 + total / postings.len() as f64).ln();
+                let factor = if token == term { 1.0 } else { 0.5 };
+                for &(doc, field) in postings {
+                    let score = idf * field.weight() * factor;
+                    let entry = best.entry(doc).or_insert((0.0, field));
+                    if score > entry.0 {
+                        *entry = (score, field);
+                    }
+                }
+            }
+            for (doc, (score, field)) in best {
+                let entry = scores.entry(doc).or_insert((0, 0.0, field));
+                entry.0 += 1;
+                entry.1 += score;
+                entry.2 = entry.2.max(field);
+            }
+        }
+
+        // every matched query word weighs more than any score
+        let mut hits: Vec<(usize, f64, Field)> = scores
+            .into_iter()
+            .map(|(doc, (matched, score, field))| {
+                (doc as usize, matched as f64 * 1000.0 + score, field)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| self.docs[a.0].name.cmp(&self.docs[b.0].name))
+        });
+        hits.truncate(MAX_TEXT_HITS);
+        hits.into_iter()
+            .map(|(doc, score, field)| TextHit {
+                doc,
+                score,
+                matched: self.matched(&self.docs[doc], field, &terms),
+            })
+            .collect()
+    }
+
+    // the entry of `field` sharing the most words with the query
+    fn matched(&self, doc: &Doc, field: Field, terms: &[String]) -> TextMatch {
+        let overlap = |text: &str| {
+            tokens(text)
+                .filter(|t| terms.iter().any(|term| t.starts_with(term.as_str())))
+                .count()
+        };
+        let best = |entries: &[String]| {
+            entries
+                .iter()
+                .max_by_key(|e| (overlap(e), std::cmp::Reverse(e.len())))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let text = match field {
+            Field::Name => doc.name.clone(),
+            Field::Description => doc.description.clone(),
+            Field::Provides => best(&doc.provides),
+            Field::File => best(&doc.files),
+        };
+        TextMatch { field, text }
+    }
+}
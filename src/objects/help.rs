@@ -0,0 +1,62 @@
+use crate::objects::keymap::{Action, Category, KeyMode, Keymap};
+
+// the `?` overlay, listing the bindings of the mode it was opened from
+#[derive(Debug, Clone)]
+pub struct Help {
+    pub mode: KeyMode,
+    pub query: String,
+    // typed keys go to the query instead of moving around
+    pub searching: bool,
+    // the first visible line
+    pub scroll: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelpLine {
+    Category(Category),
+    Binding { keys: String, action: Action },
+}
+
+impl Help {
+    pub fn new(mode: KeyMode) -> Self {
+        Self {
+            mode,
+            query: String::new(),
+            searching: false,
+            scroll: 0,
+        }
+    }
+
+    // the bindings matching the query under their category headings, a
+    // binding matches by its keys, action name, description or category
+    pub fn lines(&self, keymap: &Keymap) -> Vec<HelpLine> {
+        let query = self.query.to_lowercase();
+        let mut lines = Vec::new();
+        let mut category = None;
+        for (action, keys) in keymap.help(self.mode) {
+            let keys = keys.join(", ");
+            let matches = [
+                keys.to_lowercase(),
+                action.name().to_string(),
+                action.description().to_string(),
+                action.category().title().to_lowercase(),
+            ]
+            .iter()
+            .any(|text| text.contains(&query));
+            if !matches {
+                continue;
+            }
+            if category != Some(action.category()) {
+                category = Some(action.category());
+                lines.push(HelpLine::Category(action.category()));
+            }
+            lines.push(HelpLine::Binding { keys, action });
+        }
+        lines
+    }
+
+    pub fn scroll_by(&mut self, delta: isize, len: usize) {
+        let last = len.saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::backend::xdg;
+
+// submitted queries, oldest first, one per line in the state dir
+#[derive(Debug, Default)]
+pub struct History {
+    pub entries: Vec<String>,
+    pub limit: usize,
+    path: Option<PathBuf>,
+    // the recalled entry while going through the history with Up/Down
+    cursor: Option<usize>,
+    // the search box from before recalling or searching, restored on the way out
+    draft: String,
+    pub search: Option<ReverseSearch>,
+}
+
+// an incremental search backwards through the history, like Ctrl-R in a shell
+#[derive(Debug, Clone, Default)]
+pub struct ReverseSearch {
+    pub pattern: String,
+    pub found: Option<usize>,
+}
+
+impl History {
+    pub fn path() -> PathBuf {
+        xdg::state_dir().join("history")
+    }
+
+    pub fn new(entries: Vec<String>, limit: usize, path: Option<PathBuf>) -> Self {
+        let mut history = Self {
+            entries,
+            limit,
+            path,
+            ..Default::default()
+        };
+        history.truncate();
+        history
+    }
+
+    // a missing history file is an empty history, an unreadable one is kept
+    // in memory only so saving doesn't overwrite it
+    pub fn load(limit: usize) -> Self {
+        let path = Self::path();
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::new(
+                text.lines().map(str::to_string).collect(),
+                limit,
+                Some(path),
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Self::new(Vec::new(), limit, Some(path))
+            }
+            Err(_) => Self::new(Vec::new(), limit, None),
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = self.entries.join("\n");
+        text.push('\n');
+        fs::write(path, text)
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+    }
+
+    // an earlier copy of the same query moves to the end instead of repeating
+    pub fn push(&mut self, query: &str) -> io::Result<()> {
+        self.cursor = None;
+        let query = query.trim();
+        if query.is_empty() || self.limit == 0 {
+            return Ok(());
+        }
+        self.entries.retain(|e| e != query);
+        self.entries.push(query.to_string());
+        self.truncate();
+        self.save()
+    }
+
+    pub fn stop_recall(&mut self) {
+        self.cursor = None;
+    }
+
+    // the entry before the recalled one, None at the oldest
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let cursor = match self.cursor {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => return None,
+            Some(i) => i - 1,
+        };
+        self.cursor = Some(cursor);
+        Some(&self.entries[cursor])
+    }
+
+    // the entry after the recalled one, past the newest the draft comes back
+    pub fn newer(&mut self) -> Option<&str> {
+        let cursor = self.cursor? + 1;
+        if cursor >= self.entries.len() {
+            self.cursor = None;
+            return Some(&self.draft);
+        }
+        self.cursor = Some(cursor);
+        Some(&self.entries[cursor])
+    }
+
+    fn find(&self, pattern: &str, before: usize) -> Option<usize> {
+        (0..before.min(self.entries.len()))
+            .rev()
+            .find(|&i| self.entries[i].contains(pattern))
+    }
+
+    pub fn start_search(&mut self, current: &str) {
+        self.cursor = None;
+        self.draft = current.to_string();
+        self.search = Some(ReverseSearch::default());
+    }
+
+    // the entry the search currently shows
+    pub fn search_match(&self) -> Option<&str> {
+        let found = self.search.as_ref()?.found?;
+        self.entries.get(found).map(String::as_str)
+    }
+
+    // what the search box shows while searching
+    pub fn search_preview(&self) -> &str {
+        self.search_match().unwrap_or(&self.draft)
+    }
+
+    // a longer pattern keeps looking from the current match on
+    pub fn search_push(&mut self, c: char) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let mut pattern = search.pattern.clone();
+        pattern.push(c);
+        let from = search.found.map_or(self.entries.len(), |i| i + 1);
+        let found = self.find(&pattern, from);
+        self.search = Some(ReverseSearch { pattern, found });
+    }
+
+    pub fn search_pop(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let mut pattern = search.pattern.clone();
+        pattern.pop();
+        let found = self.find(&pattern, self.entries.len());
+        self.search = Some(ReverseSearch { pattern, found });
+    }
+
+    // the next older match, the current one stays when there is none
+    pub fn search_older(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let from = search.found.unwrap_or(self.entries.len());
+        if let Some(found) = self.find(&search.pattern, from) {
+            self.search = Some(ReverseSearch {
+                pattern: search.pattern.clone(),
+                found: Some(found),
+            });
+        }
+    }
+
+    // the query to put in the search box when the search ends
+    pub fn end_search(&mut self, accept: bool) -> String {
+        let found = match accept {
+            true => self.search_match().map(str::to_string),
+            false => None,
+        };
+        self.search = None;
+        found.unwrap_or_else(|| self.draft.clone())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::objects::{query::Query, stat::Package};
+
+// lowercase copies of the repo package names, built once so a keystroke only
+// runs the fuzzy matcher on names that contain the query characters in order
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    names: Vec<String>,
+}
+
+// the repo packages that matched the previous query, a query that only grew
+// from it is matched against these instead of every package
+#[derive(Debug)]
+pub struct Narrowed {
+    pub query: Query,
+    pub smart_case: bool,
+    pub hits: Vec<usize>,
+}
+
+impl SearchIndex {
+    pub fn new(items: &[Package]) -> Self {
+        Self {
+            names: items.iter().map(|p| fold(&p.name)).collect(),
+        }
+    }
+
+    // a case sensitive match is also a case insensitive one, so the folded
+    // names can reject candidates for every case mode
+    pub fn may_match(&self, index: usize, folded_terms: &[String]) -> bool {
+        let Some(name) = self.names.get(index) else {
+            return true;
+        };
+        folded_terms.iter().all(|term| {
+            let mut chars = name.chars();
+            term.chars().all(|t| chars.any(|c| c == t))
+        })
+    }
+}
+
+// folds like the fuzzy matcher does, one lowercase char per char
+pub fn fold(s: &str) -> String {
+    s.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
@@ -0,0 +1,765 @@
+use std::{collections::HashMap, fmt};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+// what a key binding does, named in kebab case in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    SelectNext,
+    SelectPrevious,
+    SelectFirst,
+    SelectLast,
+    SelectNone,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    // select the first, middle or last visible row, like vim's H, M and L
+    ViewTop,
+    ViewMiddle,
+    ViewBottom,
+    CenterView,
+    // installs or removes the selection, or takes the selected suggestion
+    Install,
+    Mark,
+    // what to do with the marked packages
+    Batch,
+    CleanCache,
+    EnterInsert,
+    NextTab,
+    PreviousTab,
+    ScrollDown,
+    ScrollUp,
+    ToggleSmartCase,
+    ToggleRegex,
+    ToggleFullText,
+    // searches the names of the current results
+    Find,
+    FindNext,
+    FindPrevious,
+    OpenFilters,
+    OpenColumns,
+    // move the split between the list and the info pane or maximize one
+    GrowList,
+    ShrinkList,
+    ResetLayout,
+    MaximizeList,
+    MaximizeInfo,
+    CycleSort,
+    ToggleSortDirection,
+    CheckUpdates,
+    UpgradeAur,
+    Help,
+    // insert mode
+    SubmitSearch,
+    HistorySearch,
+    HistoryOlder,
+    HistoryNewer,
+    CursorLeft,
+    CursorRight,
+    CursorStart,
+    CursorEnd,
+    WordLeft,
+    WordRight,
+    DeleteBackward,
+    DeleteForward,
+    // readline's Ctrl-W, Ctrl-U and Ctrl-K, what they remove Yank puts back
+    DeleteWordBackward,
+    KillToStart,
+    KillToEnd,
+    Yank,
+    Undo,
+    // removes a default binding in the config
+    Unbound,
+}
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::SelectNext => "select-next",
+            Action::SelectPrevious => "select-previous",
+            Action::SelectFirst => "select-first",
+            Action::SelectLast => "select-last",
+            Action::SelectNone => "select-none",
+            Action::HalfPageDown => "half-page-down",
+            Action::HalfPageUp => "half-page-up",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::ViewTop => "view-top",
+            Action::ViewMiddle => "view-middle",
+            Action::ViewBottom => "view-bottom",
+            Action::CenterView => "center-view",
+            Action::Install => "install",
+            Action::Mark => "mark",
+            Action::Batch => "batch",
+            Action::CleanCache => "clean-cache",
+            Action::EnterInsert => "enter-insert",
+            Action::NextTab => "next-tab",
+            Action::PreviousTab => "previous-tab",
+            Action::ScrollDown => "scroll-down",
+            Action::ScrollUp => "scroll-up",
+            Action::ToggleSmartCase => "toggle-smart-case",
+            Action::ToggleRegex => "toggle-regex",
+            Action::ToggleFullText => "toggle-full-text",
+            Action::Find => "find",
+            Action::FindNext => "find-next",
+            Action::FindPrevious => "find-previous",
+            Action::OpenFilters => "open-filters",
+            Action::OpenColumns => "open-columns",
+            Action::GrowList => "grow-list",
+            Action::ShrinkList => "shrink-list",
+            Action::ResetLayout => "reset-layout",
+            Action::MaximizeList => "maximize-list",
+            Action::MaximizeInfo => "maximize-info",
+            Action::CycleSort => "cycle-sort",
+            Action::ToggleSortDirection => "toggle-sort-direction",
+            Action::CheckUpdates => "check-updates",
+            Action::UpgradeAur => "upgrade-aur",
+            Action::Help => "help",
+            Action::SubmitSearch => "submit-search",
+            Action::HistorySearch => "history-search",
+            Action::HistoryOlder => "history-older",
+            Action::HistoryNewer => "history-newer",
+            Action::CursorLeft => "cursor-left",
+            Action::CursorRight => "cursor-right",
+            Action::CursorStart => "cursor-start",
+            Action::CursorEnd => "cursor-end",
+            Action::WordLeft => "word-left",
+            Action::WordRight => "word-right",
+            Action::DeleteBackward => "delete-backward",
+            Action::DeleteForward => "delete-forward",
+            Action::DeleteWordBackward => "delete-word-backward",
+            Action::KillToStart => "kill-to-start",
+            Action::KillToEnd => "kill-to-end",
+            Action::Yank => "yank",
+            Action::Undo => "undo",
+            Action::Unbound => "unbound",
+        }
+    }
+
+    // what the help overlay says it does
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit pacseen",
+            Action::SelectNext => "next package",
+            Action::SelectPrevious => "previous package",
+            Action::SelectFirst => "first package",
+            Action::SelectLast => "last package",
+            Action::SelectNone => "clear the selection",
+            Action::HalfPageDown => "half a page down",
+            Action::HalfPageUp => "half a page up",
+            Action::PageDown => "a page down",
+            Action::PageUp => "a page up",
+            Action::ViewTop => "top of the view",
+            Action::ViewMiddle => "middle of the view",
+            Action::ViewBottom => "bottom of the view",
+            Action::CenterView => "center the selection",
+            Action::Install => "install or remove the selection",
+            Action::Mark => "mark for a batch operation",
+            Action::Batch => "install or remove the marked",
+            Action::CleanCache => "clean the package cache",
+            Action::EnterInsert => "edit the search",
+            Action::NextTab => "next info tab",
+            Action::PreviousTab => "previous info tab",
+            Action::ScrollDown => "scroll the info tab down",
+            Action::ScrollUp => "scroll the info tab up",
+            Action::ToggleSmartCase => "toggle smart case",
+            Action::ToggleRegex => "toggle regex search",
+            Action::ToggleFullText => "toggle full-text search",
+            Action::Find => "find in the results",
+            Action::FindNext => "next find match",
+            Action::FindPrevious => "previous find match",
+            Action::OpenFilters => "open the filters",
+            Action::OpenColumns => "choose the table columns",
+            Action::GrowList => "widen the list",
+            Action::ShrinkList => "narrow the list",
+            Action::ResetLayout => "restore the configured layout",
+            Action::MaximizeList => "maximize the list or restore it",
+            Action::MaximizeInfo => "maximize the info pane or restore it",
+            Action::CycleSort => "next sort order",
+            Action::ToggleSortDirection => "reverse the sort order",
+            Action::CheckUpdates => "check the aur for updates",
+            Action::UpgradeAur => "upgrade outdated aur packages",
+            Action::Help => "show this help",
+            Action::SubmitSearch => "run the search",
+            Action::HistorySearch => "search the history",
+            Action::HistoryOlder => "older search",
+            Action::HistoryNewer => "newer search",
+            Action::CursorLeft => "cursor left",
+            Action::CursorRight => "cursor right",
+            Action::CursorStart => "cursor to the start",
+            Action::CursorEnd => "cursor to the end",
+            Action::WordLeft => "word left",
+            Action::WordRight => "word right",
+            Action::DeleteBackward => "delete the previous character",
+            Action::DeleteForward => "delete the next character",
+            Action::DeleteWordBackward => "delete the previous word",
+            Action::KillToStart => "delete to the start",
+            Action::KillToEnd => "delete to the end",
+            Action::Yank => "put back the deleted text",
+            Action::Undo => "undo the last edit",
+            Action::Unbound => "nothing",
+        }
+    }
+
+    // actions on the search box, only the insert table takes them
+    pub fn insert_only(&self) -> bool {
+        *self == Action::SubmitSearch
+            || matches!(self.category(), Category::History | Category::Editing)
+    }
+
+    pub fn category(&self) -> Category {
+        match self {
+            Action::SelectNext
+            | Action::SelectPrevious
+            | Action::SelectFirst
+            | Action::SelectLast
+            | Action::SelectNone
+            | Action::HalfPageDown
+            | Action::HalfPageUp
+            | Action::PageDown
+            | Action::PageUp
+            | Action::ViewTop
+            | Action::ViewMiddle
+            | Action::ViewBottom
+            | Action::CenterView => Category::Movement,
+            Action::Install
+            | Action::Mark
+            | Action::Batch
+            | Action::CleanCache
+            | Action::CheckUpdates
+            | Action::UpgradeAur => Category::Packages,
+            Action::EnterInsert
+            | Action::ToggleSmartCase
+            | Action::ToggleRegex
+            | Action::ToggleFullText
+            | Action::Find
+            | Action::FindNext
+            | Action::FindPrevious
+            | Action::OpenFilters
+            | Action::CycleSort
+            | Action::ToggleSortDirection
+            | Action::SubmitSearch => Category::Search,
+            Action::NextTab | Action::PreviousTab | Action::ScrollDown | Action::ScrollUp => {
+                Category::InfoPane
+            }
+            Action::GrowList
+            | Action::ShrinkList
+            | Action::ResetLayout
+            | Action::MaximizeList
+            | Action::MaximizeInfo => Category::Layout,
+            Action::HistorySearch | Action::HistoryOlder | Action::HistoryNewer => {
+                Category::History
+            }
+            Action::CursorLeft
+            | Action::CursorRight
+            | Action::CursorStart
+            | Action::CursorEnd
+            | Action::WordLeft
+            | Action::WordRight
+            | Action::DeleteBackward
+            | Action::DeleteForward
+            | Action::DeleteWordBackward
+            | Action::KillToStart
+            | Action::KillToEnd
+            | Action::Yank
+            | Action::Undo => Category::Editing,
+            Action::Quit | Action::Help | Action::OpenColumns | Action::Unbound => {
+                Category::General
+            }
+        }
+    }
+}
+
+// the groups of the help overlay, in the order it lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Movement,
+    Packages,
+    Search,
+    InfoPane,
+    Layout,
+    History,
+    Editing,
+    General,
+}
+
+impl Category {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Movement => "Movement",
+            Category::Packages => "Packages",
+            Category::Search => "Search",
+            Category::InfoPane => "Info pane",
+            Category::Layout => "Layout",
+            Category::History => "History",
+            Category::Editing => "Editing",
+            Category::General => "General",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMode {
+    Normal,
+    Insert,
+}
+
+// a key with its modifiers, shift is part of the character for printable keys
+// and of BackTab, which terminals report with shift held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    // the character it types in the search box, if any
+    pub fn typed(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if (self.modifiers - KeyModifiers::SHIFT).is_empty() => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+const KEY_NAMES: [(&str, KeyCode); 19] = [
+    ("enter", KeyCode::Enter),
+    ("cr", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("bs", KeyCode::Backspace),
+    ("backspace", KeyCode::Backspace),
+    ("space", KeyCode::Char(' ')),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("del", KeyCode::Delete),
+    ("lt", KeyCode::Char('<')),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+];
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    let lower = name.to_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return Some(KeyCode::F(n));
+    }
+    KEY_NAMES
+        .iter()
+        .find(|(n, _)| *n == lower)
+        .map(|(_, code)| *code)
+}
+
+// `<C-r>`, `<A-b>`, `<S-Tab>` or a key name like `<Enter>`
+fn parse_bracketed(inner: &str) -> Result<KeyChord, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = inner;
+    while let Some((prefix, tail)) = rest
+        .split_once('-')
+        .filter(|(p, t)| p.len() == 1 && !t.is_empty())
+    {
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "c" => KeyModifiers::CONTROL,
+            "a" | "m" => KeyModifiers::ALT,
+            "s" => KeyModifiers::SHIFT,
+            _ => return Err(format!("unknown modifier {}- in <{}>", prefix, inner)),
+        };
+        rest = tail;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => named_key(rest).ok_or_else(|| format!("unknown key <{}>", inner))?,
+    };
+    let code = match code {
+        KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+            modifiers -= KeyModifiers::SHIFT;
+            KeyCode::BackTab
+        }
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        code => code,
+    };
+    Ok(KeyChord::new(code, modifiers))
+}
+
+// vim style notation: `gg` is g twice, `<C-d>` is ctrl-d
+pub fn parse_keys(text: &str) -> Result<Vec<KeyChord>, String> {
+    let mut keys = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let bracketed = rest.strip_prefix('<').and_then(|r| r.split_once('>'));
+        match bracketed {
+            Some((inner, tail)) if !inner.is_empty() => {
+                keys.push(parse_bracketed(inner)?);
+                rest = tail;
+            }
+            _ => {
+                keys.push(KeyChord::plain(KeyCode::Char(c)));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    match keys.is_empty() {
+        true => Err("empty key binding".to_string()),
+        false => Ok(keys),
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char('<') => "lt".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => return write!(f, "{}", c),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            KeyCode::PageUp => "PageUp".to_string(),
+            KeyCode::PageDown => "PageDown".to_string(),
+            code => format!("{:?}", code),
+        };
+        let mut prefix = String::new();
+        for (modifier, letter) in [
+            (KeyModifiers::CONTROL, "C-"),
+            (KeyModifiers::ALT, "A-"),
+            (KeyModifiers::SHIFT, "S-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                prefix.push_str(letter);
+            }
+        }
+        write!(f, "<{}{}>", prefix, name)
+    }
+}
+
+pub fn format_keys(keys: &[KeyChord]) -> String {
+    keys.iter().map(KeyChord::to_string).collect()
+}
+
+// the `[keys.normal]` and `[keys.insert]` tables of the config, key
+// notation to action name
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct KeyConfig {
+    pub normal: HashMap<String, Action>,
+    pub insert: HashMap<String, Action>,
+}
+
+pub type Bindings = Vec<(Vec<KeyChord>, Action)>;
+
+const DEFAULT_NORMAL: [(&str, Action); 52] = [
+    ("q", Action::Quit),
+    ("<Esc>", Action::Quit),
+    ("j", Action::SelectNext),
+    ("<Down>", Action::SelectNext),
+    ("k", Action::SelectPrevious),
+    ("<Up>", Action::SelectPrevious),
+    ("gg", Action::SelectFirst),
+    ("<Home>", Action::SelectFirst),
+    ("G", Action::SelectLast),
+    ("<End>", Action::SelectLast),
+    ("h", Action::SelectNone),
+    ("<Left>", Action::SelectNone),
+    ("<C-d>", Action::HalfPageDown),
+    ("<C-u>", Action::HalfPageUp),
+    ("<C-f>", Action::PageDown),
+    ("<PageDown>", Action::PageDown),
+    ("<C-b>", Action::PageUp),
+    ("<PageUp>", Action::PageUp),
+    ("H", Action::ViewTop),
+    ("M", Action::ViewMiddle),
+    ("L", Action::ViewBottom),
+    ("zz", Action::CenterView),
+    ("l", Action::Install),
+    ("<Enter>", Action::Install),
+    ("m", Action::Mark),
+    ("b", Action::Batch),
+    ("C", Action::CleanCache),
+    ("i", Action::EnterInsert),
+    ("<Tab>", Action::EnterInsert),
+    ("]", Action::NextTab),
+    ("[", Action::PreviousTab),
+    ("J", Action::ScrollDown),
+    ("K", Action::ScrollUp),
+    ("c", Action::ToggleSmartCase),
+    ("r", Action::ToggleRegex),
+    ("t", Action::ToggleFullText),
+    ("/", Action::Find),
+    ("n", Action::FindNext),
+    ("N", Action::FindPrevious),
+    ("f", Action::OpenFilters),
+    ("v", Action::OpenColumns),
+    ("<C-w>>", Action::GrowList),
+    ("<C-w><lt>", Action::ShrinkList),
+    ("<C-w>=", Action::ResetLayout),
+    ("<C-w>o", Action::MaximizeList),
+    ("<C-w>i", Action::MaximizeInfo),
+    ("s", Action::CycleSort),
+    ("S", Action::ToggleSortDirection),
+    ("u", Action::CheckUpdates),
+    ("U", Action::UpgradeAur),
+    ("?", Action::Help),
+    ("<F1>", Action::Help),
+];
+
+const DEFAULT_INSERT: [(&str, Action); 25] = [
+    ("<Enter>", Action::SubmitSearch),
+    ("<Tab>", Action::SubmitSearch),
+    ("<C-r>", Action::HistorySearch),
+    ("<Up>", Action::HistoryOlder),
+    ("<Down>", Action::HistoryNewer),
+    ("<Left>", Action::CursorLeft),
+    ("<Right>", Action::CursorRight),
+    ("<C-a>", Action::CursorStart),
+    ("<Home>", Action::CursorStart),
+    ("<C-e>", Action::CursorEnd),
+    ("<End>", Action::CursorEnd),
+    ("<A-b>", Action::WordLeft),
+    ("<C-Left>", Action::WordLeft),
+    ("<A-f>", Action::WordRight),
+    ("<C-Right>", Action::WordRight),
+    ("<BS>", Action::DeleteBackward),
+    ("<Del>", Action::DeleteForward),
+    ("<C-w>", Action::DeleteWordBackward),
+    ("<C-u>", Action::KillToStart),
+    ("<C-k>", Action::KillToEnd),
+    ("<C-y>", Action::Yank),
+    ("<C-z>", Action::Undo),
+    ("<C-_>", Action::Undo),
+    // what crossterm reports for ctrl-_ on most terminals
+    ("<C-7>", Action::Undo),
+    ("<F1>", Action::Help),
+];
+
+// how a key press resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyResult {
+    Action(Action),
+    // the start of a longer binding, waiting for the next key
+    Pending,
+    // not bound, in insert mode it gets typed
+    Unbound(KeyChord),
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub normal: Bindings,
+    pub insert: Bindings,
+    // the keys typed so far of a multi-key binding
+    pending: Vec<KeyChord>,
+    // a count typed before a normal mode binding, the 5 of `5j`
+    count: Option<usize>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeyConfig::default()).expect("the default keymap is valid")
+    }
+}
+
+impl Keymap {
+    // the defaults with the config on top. a configured key replaces the
+    // defaults it overlaps with, overlapping keys within the config are an error
+    pub fn new(config: &KeyConfig) -> Result<Self, String> {
+        let mut insert_only: Vec<(&String, &Action)> = config
+            .normal
+            .iter()
+            .filter(|(_, action)| action.insert_only())
+            .collect();
+        insert_only.sort_by_key(|(text, _)| *text);
+        if let Some((text, action)) = insert_only.first() {
+            return Err(format!(
+                "[keys.normal] {}: {} is only available in [keys.insert]",
+                text,
+                action.name()
+            ));
+        }
+        Ok(Self {
+            normal: merge(&DEFAULT_NORMAL, &config.normal, "normal")?,
+            insert: merge(&DEFAULT_INSERT, &config.insert, "insert")?,
+            pending: Vec::new(),
+            count: None,
+        })
+    }
+
+    pub fn bindings(&self, mode: KeyMode) -> &Bindings {
+        match mode {
+            KeyMode::Normal => &self.normal,
+            KeyMode::Insert => &self.insert,
+        }
+    }
+
+    // every action bound in `mode` with its keys, grouped by category in
+    // the order the bindings were made
+    pub fn help(&self, mode: KeyMode) -> Vec<(Action, Vec<String>)> {
+        let mut entries: Vec<(Action, Vec<String>)> = Vec::new();
+        for (keys, action) in self.bindings(mode) {
+            let keys = format_keys(keys);
+            match entries.iter_mut().find(|(a, _)| a == action) {
+                Some((_, all)) => all.push(keys),
+                None => entries.push((*action, vec![keys])),
+            }
+        }
+        entries.sort_by_key(|(action, _)| action.category());
+        entries
+    }
+
+    pub fn pending(&self) -> &[KeyChord] {
+        &self.pending
+    }
+
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+        self.count = None;
+    }
+
+    pub fn count(&self) -> Option<usize> {
+        self.count
+    }
+
+    // the count for the action the last key resolved to
+    pub fn take_count(&mut self) -> Option<usize> {
+        self.count.take()
+    }
+
+    // a key that breaks off a sequence gives the earlier keys back as
+    // unbound and then resolves on its own. in normal mode digits that start
+    // no binding make up a count, a leading 0 is not one
+    pub fn press(&mut self, mode: KeyMode, key: KeyChord) -> Vec<KeyResult> {
+        let mut results = Vec::new();
+        let counting = mode == KeyMode::Normal
+            && self.pending.is_empty()
+            && matches!(self.lookup(mode, &[key]), Lookup::None);
+        let digit = key
+            .typed()
+            .and_then(|c| c.to_digit(10))
+            .filter(|d| counting && (*d > 0 || self.count.is_some()));
+        if let Some(digit) = digit {
+            let count = self.count.unwrap_or(0).saturating_mul(10);
+            self.count = Some(count.saturating_add(digit as usize));
+            results.push(KeyResult::Pending);
+            return results;
+        }
+        self.pending.push(key);
+        loop {
+            match self.lookup(mode, &self.pending) {
+                Lookup::Exact(action) => {
+                    self.pending.clear();
+                    results.push(KeyResult::Action(action));
+                    return results;
+                }
+                Lookup::Prefix => {
+                    results.push(KeyResult::Pending);
+                    return results;
+                }
+                Lookup::None if self.pending.len() == 1 => {
+                    self.pending.clear();
+                    self.count = None;
+                    results.push(KeyResult::Unbound(key));
+                    return results;
+                }
+                Lookup::None => {
+                    let dropped: Vec<KeyChord> =
+                        self.pending.drain(..self.pending.len() - 1).collect();
+                    results.extend(dropped.into_iter().map(KeyResult::Unbound));
+                }
+            }
+        }
+    }
+
+    fn lookup(&self, mode: KeyMode, keys: &[KeyChord]) -> Lookup {
+        let mut found = Lookup::None;
+        for (seq, action) in self.bindings(mode) {
+            if seq.as_slice() == keys {
+                return Lookup::Exact(*action);
+            }
+            if seq.starts_with(keys) {
+                found = Lookup::Prefix;
+            }
+        }
+        found
+    }
+}
+
+enum Lookup {
+    Exact(Action),
+    Prefix,
+    None,
+}
+
+fn overlaps(a: &[KeyChord], b: &[KeyChord]) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+fn merge(
+    defaults: &[(&str, Action)],
+    config: &HashMap<String, Action>,
+    table: &str,
+) -> Result<Bindings, String> {
+    let mut user: Bindings = Vec::new();
+    let mut errors = Vec::new();
+    let mut entries: Vec<(&String, &Action)> = config.iter().collect();
+    entries.sort_by_key(|(text, _)| *text);
+    for (text, action) in entries {
+        let keys = parse_keys(text).map_err(|e| format!("[keys.{}] {}: {}", table, text, e))?;
+        if let Some((other, other_action)) = user.iter().find(|(seq, _)| overlaps(seq, &keys)) {
+            errors.push(format!(
+                "{} ({}) and {} ({}) overlap",
+                format_keys(other),
+                other_action.name(),
+                format_keys(&keys),
+                action.name()
+            ));
+            continue;
+        }
+        user.push((keys, *action));
+    }
+    if !errors.is_empty() {
+        return Err(format!(
+            "conflicting key bindings in [keys.{}]: {}",
+            table,
+            errors.join(", ")
+        ));
+    }
+
+    let mut bindings: Bindings = defaults
+        .iter()
+        .map(|(text, action)| (parse_keys(text).expect("valid default key"), *action))
+        .filter(|(seq, _)| !user.iter().any(|(keys, _)| overlaps(seq, keys)))
+        .collect();
+    bindings.extend(
+        user.into_iter()
+            .filter(|(_, action)| *action != Action::Unbound),
+    );
+    Ok(bindings)
+}
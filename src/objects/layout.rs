@@ -0,0 +1,189 @@
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+use crate::{backend::xdg, objects::config::Config};
+
+// the steps the split moves in and how far it goes, in percent of the list
+pub const SPLIT_STEP: u16 = 5;
+pub const SPLIT_MIN: u16 = 20;
+pub const SPLIT_MAX: u16 = 80;
+
+// the breakpoints and defaults of the layout, the [layout] table of the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    // share of the list, the width side by side or the height stacked
+    pub split: u16,
+    // terminals narrower than this stack the list above the info pane
+    pub stack_below: u16,
+    // terminals narrower than this only show the list
+    pub hide_info_below: u16,
+    // lines of the notice box, 0 hides it
+    pub notice_height: u16,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            split: 35,
+            stack_below: 100,
+            hide_info_below: 60,
+            notice_height: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pane {
+    List,
+    Info,
+}
+
+// what the layout keys changed, the split is kept in
+// ~/.local/state/pacseen/layout.toml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaneLayout {
+    pub split: u16,
+    // every start shows both panes again
+    #[serde(skip)]
+    pub maximized: Option<Pane>,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self::new(&LayoutConfig::default())
+    }
+}
+
+// where render puts the panes, an empty rect for a hidden one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PaneAreas {
+    // the search box, the filter chips and the list
+    pub list: Rect,
+    pub info: Rect,
+    pub notice: Rect,
+}
+
+impl PaneLayout {
+    pub fn new(config: &LayoutConfig) -> Self {
+        Self {
+            split: config.split.clamp(SPLIT_MIN, SPLIT_MAX),
+            maximized: None,
+        }
+    }
+
+    pub fn path() -> PathBuf {
+        xdg::state_dir().join("layout.toml")
+    }
+
+    // a missing or unreadable state file keeps the configured layout
+    pub fn load(config: &LayoutConfig) -> Self {
+        Self::load_from(&Self::path(), &Config::path(), config)
+    }
+
+    // the config wins when it was edited after the split last changed
+    pub fn load_from(state: &Path, config_file: &Path, config: &LayoutConfig) -> Self {
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified(config_file) > modified(state) {
+            return Self::new(config);
+        }
+        fs::read_to_string(state)
+            .ok()
+            .and_then(|text| toml::from_str::<Self>(&text).ok())
+            .map(|layout| Self {
+                split: layout.split.clamp(SPLIT_MIN, SPLIT_MAX),
+                ..layout
+            })
+            .unwrap_or_else(|| Self::new(config))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    // back to the configured split, a missing state file is already there
+    pub fn remove_saved() -> io::Result<()> {
+        match fs::remove_file(Self::path()) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    // grows the list by steps, shrinks it for negative ones
+    pub fn resize(&mut self, steps: i32) {
+        let split = self.split as i32 + steps * SPLIT_STEP as i32;
+        self.split = split.clamp(SPLIT_MIN as i32, SPLIT_MAX as i32) as u16;
+    }
+
+    // maximizes the pane, or restores the split when it already is
+    pub fn toggle_maximized(&mut self, pane: Pane) {
+        self.maximized = match self.maximized {
+            Some(maximized) if maximized == pane => None,
+            _ => Some(pane),
+        };
+    }
+
+    pub fn areas(&self, area: Rect, config: &LayoutConfig) -> PaneAreas {
+        let (list, info) = match self.maximized {
+            Some(Pane::List) => (true, false),
+            Some(Pane::Info) => (false, true),
+            None => (true, area.width >= config.hide_info_below),
+        };
+        let split = [
+            Constraint::Percentage(self.split),
+            Constraint::Percentage(100 - self.split),
+        ];
+        // side by side the notice sits below the info pane, else below both
+        if list && info && area.width >= config.stack_below {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(split)
+                .split(area);
+            let right = stack(chunks[1], Constraint::Min(3), config.notice_height);
+            return PaneAreas {
+                list: chunks[0],
+                info: right[0],
+                notice: right[1],
+            };
+        }
+        let chunks = stack(area, Constraint::Min(0), config.notice_height);
+        let (list, info) = match (list, info) {
+            (true, true) => {
+                let panes = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(split)
+                    .split(chunks[0]);
+                (panes[0], panes[1])
+            }
+            (true, false) => (chunks[0], Rect::default()),
+            _ => (Rect::default(), chunks[0]),
+        };
+        PaneAreas {
+            list,
+            info,
+            notice: chunks[1],
+        }
+    }
+}
+
+// a pane above the notice box
+fn stack(area: Rect, pane: Constraint, notice_height: u16) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([pane, Constraint::Length(notice_height)])
+        .split(area)
+}
@@ -0,0 +1,216 @@
+use std::cmp::Ordering;
+
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Subsequence,
+    Substring,
+    WordBoundary,
+    Prefix,
+    Exact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub kind: MatchKind,
+    pub score: i64,
+    // char indices of the matched characters in the name
+    pub positions: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    // best match first, shorter names win within the same score
+    pub fn rank(a: (&FuzzyMatch, &str), b: (&FuzzyMatch, &str)) -> Ordering {
+        b.0.score
+            .cmp(&a.0.score)
+            .then_with(|| a.1.len().cmp(&b.1.len()))
+            .then_with(|| a.1.cmp(b.1))
+    }
+}
+
+// smart case: the query only matches case sensitively when it has an uppercase
+// letter, with smart_case off every query is case sensitive
+pub fn fuzzy_match(name: &str, query: &str, smart_case: bool) -> Option<FuzzyMatch> {
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            kind: MatchKind::Subsequence,
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let ignore_case = smart_case && !query.chars().any(char::is_uppercase);
+    let fold = |c: char| match ignore_case {
+        true => c.to_lowercase().next().unwrap_or(c),
+        false => c,
+    };
+    let name_chars: Vec<char> = name.chars().map(fold).collect();
+    let query_chars: Vec<char> = query.chars().map(fold).collect();
+    let len = query_chars.len();
+
+    let substring = name_chars
+        .windows(len)
+        .enumerate()
+        .filter(|(_, w)| *w == query_chars.as_slice())
+        .map(|(i, _)| i)
+        .min_by_key(|&i| (!is_boundary(&name_chars, i), i));
+
+    let (kind, positions) = match substring {
+        Some(0) if name_chars.len() == len => (MatchKind::Exact, (0..len).collect()),
+        Some(0) => (MatchKind::Prefix, (0..len).collect()),
+        Some(i) if is_boundary(&name_chars, i) => (MatchKind::WordBoundary, (i..i + len).collect()),
+        Some(i) => (MatchKind::Substring, (i..i + len).collect()),
+        None => (
+            MatchKind::Subsequence,
+            subsequence(&name_chars, &query_chars)?,
+        ),
+    };
+
+    let base = match kind {
+        MatchKind::Exact => 4000,
+        MatchKind::Prefix => 3000,
+        MatchKind::WordBoundary => 2000,
+        MatchKind::Substring => 1500,
+        MatchKind::Subsequence => 1000 + subsequence_bonus(&name_chars, &positions),
+    };
+    Some(FuzzyMatch {
+        kind,
+        score: base - name_chars.len() as i64,
+        positions,
+    })
+}
+
+fn is_boundary(chars: &[char], i: usize) -> bool {
+    i == 0 || !chars[i - 1].is_alphanumeric()
+}
+
+// prefers characters that start a word, so `pr` finds the `p` and `r` of
+// `python-requests` rather than the `r` inside `python`
+fn subsequence(name: &[char], query: &[char]) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut from = 0;
+    for (qi, &qc) in query.iter().enumerate() {
+        let remaining = &query[qi + 1..];
+        let candidates = (from..name.len()).filter(|&i| name[i] == qc);
+        let mut chosen = None;
+        for i in candidates {
+            if chosen.is_none() {
+                chosen = Some(i);
+            }
+            if is_boundary(name, i) && subsequence_exists(&name[i + 1..], remaining) {
+                chosen = Some(i);
+                break;
+            }
+        }
+        let i = chosen?;
+        positions.push(i);
+        from = i + 1;
+    }
+    Some(positions)
+}
+
+fn subsequence_exists(name: &[char], query: &[char]) -> bool {
+    let mut name = name.iter();
+    query.iter().all(|q| name.any(|c| c == q))
+}
+
+fn subsequence_bonus(name: &[char], positions: &[usize]) -> i64 {
+    let mut bonus = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        if is_boundary(name, i) {
+            bonus += 30;
+        }
+        if n > 0 && positions[n - 1] + 1 == i {
+            bonus += 15;
+        }
+    }
+    let span = positions.last().unwrap_or(&0) - positions.first().unwrap_or(&0);
+    // stay below the substring tier
+    bonus.min(400) - span as i64
+}
+
+pub fn regex_match(re: &Regex, name: &str) -> Option<FuzzyMatch> {
+    let found = re.find(name)?;
+    let start = name[..found.start()].chars().count();
+    let len = found.as_str().chars().count();
+    Some(FuzzyMatch {
+        kind: MatchKind::Substring,
+        score: 0,
+        positions: (start..start + len).collect(),
+    })
+}
+
+// the longest run of plain characters every match of `pattern` has to
+// contain, which is what gets sent to the aur since its rpc has no regex
+// support. a top level alternation makes every run optional, so it gets no
+// keyword
+pub fn regex_keyword(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut runs = Vec::new();
+    let mut run = String::new();
+    // whether the last token was the literal at the end of `run`
+    let mut after_literal = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let literal = match chars[i] {
+            '\\' => {
+                i += 1;
+                chars.get(i).copied().filter(|c| !c.is_alphanumeric())
+            }
+            open @ ('(' | '[') => {
+                let close = if open == '(' { ')' } else { ']' };
+                let mut depth = 0;
+                while i < chars.len() {
+                    match chars[i] {
+                        '\\' => i += 1,
+                        c if c == open => depth += 1,
+                        c if c == close => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                None
+            }
+            '|' => return String::new(),
+            quantifier @ ('*' | '?' | '{') => {
+                let mut optional = quantifier != '{';
+                if quantifier == '{' {
+                    let start = i + 1;
+                    while i < chars.len() && chars[i] != '}' {
+                        i += 1;
+                    }
+                    let min: String = chars[start..i]
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect();
+                    optional = min.parse::<usize>().is_ok_and(|min| min == 0);
+                }
+                // the quantified character may be missing, a lazy `?` after
+                // another quantifier quantifies nothing
+                if optional && after_literal {
+                    run.pop();
+                }
+                None
+            }
+            '+' | '.' | '^' | '$' | ')' | ']' => None,
+            c => Some(c),
+        };
+        after_literal = literal.is_some();
+        match literal {
+            Some(c) => run.push(c),
+            None if !run.is_empty() => runs.push(std::mem::take(&mut run)),
+            None => {}
+        }
+        i += 1;
+    }
+    runs.push(run);
+    runs.into_iter()
+        .filter(|r| r.chars().count() >= 2)
+        .max_by_key(|r| r.chars().count())
+        .unwrap_or_default()
+}
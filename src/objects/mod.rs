@@ -1 +1,18 @@
+pub mod columns;
+pub mod config;
+pub mod dialog;
+pub mod edit;
+pub mod filters;
+pub mod find;
+pub mod fulltext;
+pub mod help;
+pub mod history;
+pub mod index;
+pub mod keymap;
+pub mod layout;
+pub mod matcher;
+pub mod mouse;
+pub mod query;
 pub mod stat;
+pub mod suggest;
+pub mod theme;
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use ratatui::layout::{Position, Rect};
+
+use crate::objects::{keymap::Action, stat::InfoTab};
+
+// the longest gap between the clicks of a double click
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+// rows a wheel notch moves
+pub const WHEEL_LINES: usize = 3;
+
+// where the last frame drew the parts that take clicks
+#[derive(Debug, Clone, Default)]
+pub struct Areas {
+    pub search: Rect,
+    // inside the border of the list
+    pub list: Rect,
+    // the result or suggestion on each line of the list, None for headings
+    pub rows: Vec<Option<usize>>,
+    pub info: Rect,
+    pub tabs: Vec<(InfoTab, Rect)>,
+    pub menu: Rect,
+}
+
+impl Areas {
+    pub fn row_at(&self, pos: Position) -> Option<usize> {
+        if !self.list.contains(pos) {
+            return None;
+        }
+        self.rows
+            .get((pos.y - self.list.y) as usize)
+            .copied()
+            .flatten()
+    }
+
+    pub fn tab_at(&self, pos: Position) -> Option<InfoTab> {
+        self.tabs
+            .iter()
+            .find(|(_, area)| area.contains(pos))
+            .map(|(tab, _)| *tab)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Click {
+    pub at: Instant,
+    pub row: usize,
+}
+
+// the actions a right click on a package offers
+#[derive(Debug, Clone)]
+pub struct ContextMenu {
+    // the cell that was clicked, the menu opens below it
+    pub position: Position,
+    pub cursor: usize,
+}
+
+impl ContextMenu {
+    pub const ACTIONS: [Action; 6] = [
+        Action::Install,
+        Action::Mark,
+        Action::Batch,
+        Action::CheckUpdates,
+        Action::OpenFilters,
+        Action::Help,
+    ];
+
+    pub fn new(position: Position) -> Self {
+        Self {
+            position,
+            cursor: 0,
+        }
+    }
+
+    pub fn action(&self) -> Action {
+        Self::ACTIONS[self.cursor]
+    }
+}
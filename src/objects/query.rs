@@ -0,0 +1,493 @@
+use std::{fmt, ops::Range};
+
+use crate::objects::{
+    matcher::{FuzzyMatch, MatchKind, fuzzy_match},
+    stat::{ItemRepo, Package},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Cmp {
+    pub fn apply(self, value: u64, limit: u64) -> bool {
+        match self {
+            Cmp::Lt => value < limit,
+            Cmp::Le => value <= limit,
+            Cmp::Eq => value == limit,
+            Cmp::Ge => value >= limit,
+            Cmp::Gt => value > limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    // free text, matched against the name
+    Text(String),
+    Repo(String),
+    Installed(bool),
+    Aur(bool),
+    Size(Cmp, u64), // KiB, like Package::size
+    Votes(Cmp, u64),
+    Desc(String),
+    Provides(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Term(Term),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    // char offset into the search string
+    pub position: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query error at {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Query::And(Vec::new())
+    }
+}
+
+impl Query {
+    // `a b` and `a AND b` are conjunctions, `a OR b` binds looser, `-term`
+    // excludes, parentheses group
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: input.chars().count(),
+        };
+        let query = parser.or_expr()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some((Token::Close, at)) => Err(error(at, "unmatched ')'")),
+            Some((_, at)) => Err(error(at, "unexpected token")),
+        }
+    }
+
+    pub fn matches(&self, pkg: &Package, smart_case: bool) -> bool {
+        self.matches_in(pkg, smart_case, false)
+    }
+
+    // below an odd number of `-` text is excluded by substring, excluding
+    // every fuzzy match would also drop names that merely contain the
+    // letters in order
+    fn matches_in(&self, pkg: &Package, smart_case: bool, excluded: bool) -> bool {
+        match self {
+            Query::Term(term) => term.matches(pkg, smart_case, excluded),
+            Query::Not(query) => !query.matches_in(pkg, smart_case, !excluded),
+            Query::And(queries) => queries
+                .iter()
+                .all(|q| q.matches_in(pkg, smart_case, excluded)),
+            Query::Or(queries) => queries
+                .iter()
+                .any(|q| q.matches_in(pkg, smart_case, excluded)),
+        }
+    }
+
+    // free text terms that are not excluded, these drive ranking and the aur search
+    pub fn text_terms(&self) -> Vec<&str> {
+        let mut terms = Vec::new();
+        self.collect_text(&mut terms);
+        terms
+    }
+
+    fn collect_text<'a>(&'a self, terms: &mut Vec<&'a str>) {
+        match self {
+            Query::Term(Term::Text(text)) => terms.push(text),
+            Query::Term(_) | Query::Not(_) => {}
+            Query::And(queries) | Query::Or(queries) => {
+                queries.iter().for_each(|q| q.collect_text(terms))
+            }
+        }
+    }
+
+    pub fn has_text(&self) -> bool {
+        !self.text_terms().is_empty()
+    }
+
+    // the aur rpc takes a single keyword and wants at least two characters
+    pub fn aur_keyword(&self) -> String {
+        if self.excludes_aur() {
+            return String::new();
+        }
+        self.text_terms()
+            .into_iter()
+            .filter(|t| t.chars().count() >= 2)
+            .max_by_key(|t| t.len())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    // text terms every match has to satisfy, unlike text_terms this leaves
+    // out the alternatives of an OR
+    pub fn required_text(&self) -> Vec<&str> {
+        match self {
+            Query::And(queries) => queries.iter().filter_map(Query::as_text).collect(),
+            query => query.as_text().into_iter().collect(),
+        }
+    }
+
+    // whether every package matching self also matches `wider`, which holds
+    // for plain text queries whose terms only got longer or more numerous
+    pub fn narrows(&self, wider: &Query) -> bool {
+        let (Some(terms), Some(wider)) = (self.plain_text(), wider.plain_text()) else {
+            return false;
+        };
+        wider.len() <= terms.len() && wider.iter().zip(&terms).all(|(w, t)| t.starts_with(w))
+    }
+
+    fn plain_text(&self) -> Option<Vec<&str>> {
+        match self {
+            Query::And(queries) => queries.iter().map(Query::as_text).collect(),
+            query => query.as_text().map(|t| vec![t]),
+        }
+    }
+
+    fn as_text(&self) -> Option<&str> {
+        match self {
+            Query::Term(Term::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn excludes_aur(&self) -> bool {
+        let excludes = |q: &Query| match q {
+            Query::Term(Term::Aur(false)) => true,
+            Query::Term(Term::Repo(repo)) => !repo.eq_ignore_ascii_case("aur"),
+            Query::Not(inner) => matches!(**inner, Query::Term(Term::Aur(true))),
+            _ => false,
+        };
+        match self {
+            Query::And(queries) => queries.iter().any(excludes),
+            query => excludes(query),
+        }
+    }
+
+    // None when the package does not match, otherwise the combined fuzzy
+    // match of all text terms
+    pub fn rank(&self, pkg: &Package, smart_case: bool) -> Option<FuzzyMatch> {
+        if !self.matches(pkg, smart_case) {
+            return None;
+        }
+        let mut rank = FuzzyMatch {
+            kind: MatchKind::Subsequence,
+            score: 0,
+            positions: Vec::new(),
+        };
+        for text in self.text_terms() {
+            if let Some(m) = fuzzy_match(&pkg.name, text, smart_case) {
+                rank.kind = rank.kind.max(m.kind);
+                rank.score += m.score;
+                rank.positions.extend(m.positions);
+            }
+        }
+        rank.positions.sort_unstable();
+        rank.positions.dedup();
+        Some(rank)
+    }
+}
+
+impl Term {
+    fn matches(&self, pkg: &Package, smart_case: bool, excluded: bool) -> bool {
+        let votes = match pkg.repo {
+            ItemRepo::AUR(votes) => Some(votes as u64),
+            _ => None,
+        };
+        match self {
+            Term::Text(text) if excluded => {
+                contains(&pkg.name, text) || (votes.is_some() && contains(&pkg.descipt, text))
+            }
+            Term::Text(text) => {
+                fuzzy_match(&pkg.name, text, smart_case).is_some()
+                    // the aur search also matched on descriptions
+                    || (votes.is_some() && contains(&pkg.descipt, text))
+            }
+            Term::Repo(repo) => pkg.repo.name().eq_ignore_ascii_case(repo),
+            Term::Installed(installed) => pkg.is_installed == *installed,
+            Term::Aur(aur) => votes.is_some() == *aur,
+            // the aur search results carry no size
+            Term::Size(cmp, size) => votes.is_none() && cmp.apply(pkg.size, *size),
+            Term::Votes(cmp, limit) => votes.is_some_and(|v| cmp.apply(v, *limit)),
+            Term::Desc(text) => contains(&pkg.descipt, text),
+            Term::Provides(name) => pkg.provides.iter().any(|p| contains(p, name)),
+        }
+    }
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn error(position: usize, message: &str) -> QueryError {
+    QueryError {
+        message: message.to_string(),
+        position,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    Ok(tokenize_spans(input)?
+        .into_iter()
+        .map(|(token, span)| (token, span.start))
+        .collect())
+}
+
+// the tokens with the chars of `input` each one was read from
+fn tokenize_spans(input: &str) -> Result<Vec<(Token, Range<usize>)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push((if c == '(' { Token::Open } else { Token::Close }, i..i + 1));
+            i += 1;
+        } else if c == '-' && chars.get(i + 1).is_some_and(|n| !n.is_whitespace()) {
+            tokens.push((Token::Not, i..i + 1));
+            i += 1;
+        } else if c == '-' {
+            return Err(error(i, "nothing to exclude after '-'"));
+        } else {
+            let start = i;
+            let mut word = String::new();
+            let mut quoted = false;
+            while i < chars.len() {
+                let c = chars[i];
+                if c == '"' {
+                    quoted = !quoted;
+                } else if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                    break;
+                } else {
+                    word.push(c);
+                }
+                i += 1;
+            }
+            if quoted {
+                return Err(error(start, "unterminated quote"));
+            }
+            let token = match word.as_str() {
+                "AND" | "&&" => Token::And,
+                "OR" | "||" | "|" => Token::Or,
+                _ => Token::Word(word),
+            };
+            tokens.push((token, start..i));
+        }
+    }
+    Ok(tokens)
+}
+
+// the free text terms of `input` that are not excluded, with the chars each
+// one was typed as
+pub fn text_spans(input: &str) -> Vec<(String, Range<usize>)> {
+    let Ok(tokens) = tokenize_spans(input) else {
+        return Vec::new();
+    };
+    let mut spans = Vec::new();
+    for (n, (token, span)) in tokens.iter().enumerate() {
+        let excluded = n > 0 && tokens[n - 1].0 == Token::Not;
+        if let (Token::Word(word), false) = (token, excluded)
+            && let Ok(Term::Text(text)) = parse_term(word, span.start)
+        {
+            spans.push((text, span.clone()));
+        }
+    }
+    spans
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(Token, usize)> {
+        self.tokens.get(self.pos).cloned()
+    }
+
+    fn or_expr(&mut self) -> Result<Query, QueryError> {
+        let mut branches = vec![self.and_expr()?];
+        while let Some((Token::Or, at)) = self.peek() {
+            self.pos += 1;
+            let branch = self.and_expr()?;
+            if branch == Query::default() {
+                return Err(error(at, "OR needs a term on both sides"));
+            }
+            branches.push(branch);
+        }
+        if branches.len() > 1 && branches[0] == Query::default() {
+            return Err(error(0, "OR needs a term on both sides"));
+        }
+        Ok(match branches.len() {
+            1 => branches.remove(0),
+            _ => Query::Or(branches),
+        })
+    }
+
+    fn and_expr(&mut self) -> Result<Query, QueryError> {
+        let mut terms = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some((Token::Or | Token::Close, _)) => break,
+                Some((Token::And, at)) => {
+                    self.pos += 1;
+                    if terms.is_empty()
+                        || matches!(
+                            self.peek(),
+                            None | Some((Token::Or | Token::Close | Token::And, _))
+                        )
+                    {
+                        return Err(error(at, "AND needs a term on both sides"));
+                    }
+                }
+                Some(_) => terms.push(self.unary()?),
+            }
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Query::And(terms),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Query, QueryError> {
+        match self.peek() {
+            Some((Token::Not, _)) => {
+                self.pos += 1;
+                Ok(Query::Not(Box::new(self.atom()?)))
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Query, QueryError> {
+        let Some((token, at)) = self.peek() else {
+            return Err(error(self.end, "unexpected end of query"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Word(word) => parse_term(&word, at).map(Query::Term),
+            Token::Open => {
+                let query = self.or_expr()?;
+                match self.peek() {
+                    Some((Token::Close, _)) => {
+                        self.pos += 1;
+                        Ok(query)
+                    }
+                    _ => Err(error(at, "unmatched '('")),
+                }
+            }
+            Token::Close => Err(error(at, "unmatched ')'")),
+            Token::Not => Err(error(at, "double exclusion")),
+            Token::And | Token::Or => Err(error(at, "expected a term")),
+        }
+    }
+}
+
+fn parse_term(word: &str, at: usize) -> Result<Term, QueryError> {
+    if let Some(op_at) = word.find(['<', '>', '=']) {
+        let key = word[..op_at].to_ascii_lowercase();
+        if key == "size" || key == "votes" {
+            let rest = &word[op_at..];
+            let (cmp, value) = [
+                (">=", Cmp::Ge),
+                ("<=", Cmp::Le),
+                (">", Cmp::Gt),
+                ("<", Cmp::Lt),
+                ("=", Cmp::Eq),
+            ]
+            .iter()
+            .find_map(|(op, cmp)| rest.strip_prefix(op).map(|v| (*cmp, v)))
+            .unwrap_or((Cmp::Eq, rest));
+            return match key.as_str() {
+                "size" => parse_size(value)
+                    .map(|size| Term::Size(cmp, size))
+                    .ok_or_else(|| error(at, "invalid size, expected e.g. 50M")),
+                _ => value
+                    .parse()
+                    .map(|votes| Term::Votes(cmp, votes))
+                    .map_err(|_| error(at, "invalid number of votes")),
+            };
+        }
+    }
+
+    let Some((key, value)) = word.split_once(':') else {
+        return Ok(Term::Text(word.to_string()));
+    };
+    if value.is_empty() {
+        return Err(error(at, &format!("missing value for '{}'", key)));
+    }
+    match key.to_ascii_lowercase().as_str() {
+        "name" => Ok(Term::Text(value.to_string())),
+        "repo" => Ok(Term::Repo(value.to_string())),
+        "desc" => Ok(Term::Desc(value.to_string())),
+        "provides" => Ok(Term::Provides(value.to_string())),
+        "installed" => parse_bool(value)
+            .map(Term::Installed)
+            .ok_or_else(|| error(at, "installed: expects yes or no")),
+        "aur" => parse_bool(value)
+            .map(Term::Aur)
+            .ok_or_else(|| error(at, "aur: expects yes or no")),
+        _ => Err(error(at, &format!("unknown filter '{}'", key))),
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// "50M", "1.5GiB", "300k", "2048" (bytes) into KiB
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let unit = unit.to_ascii_lowercase();
+    let factor = match unit.trim_end_matches("ib").trim_end_matches('b') {
+        "" if unit.is_empty() || unit == "b" => 1.0 / 1024.0,
+        "k" => 1.0,
+        "m" => 1024.0,
+        "g" => 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * factor).round() as u64)
+}
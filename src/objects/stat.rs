@@ -1,13 +1,50 @@
-use std::{error::Error, thread, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    env,
+    error::Error,
+    thread,
+    time::Duration,
+};
 
 use ratatui::widgets::ListState;
+use regex::{Regex, RegexBuilder};
 use tokio::{
     sync::mpsc::{self, error::TryRecvError},
     task::JoinHandle,
     time::sleep,
 };
 
-use crate::backend::{aur::get_aur_packages, load_repo_packages};
+use crate::backend::{
+    aur::{AurClient, get_aur_packages},
+    comments::{AurComments, fetch_aur_comments},
+    details::{
+        PackageDeps, PackageFiles, PackageHistory, fetch_dependencies, load_files, load_history,
+    },
+    fulltext::open_index,
+    load_foreign_packages, load_repo_packages,
+    pkgbuild::{self, AurSource, fetch_aur_source},
+    updates::{UpdateReport, check_aur_updates},
+};
+use crate::objects::{
+    columns::Columns,
+    config::Config,
+    dialog::Dialog,
+    edit::{EditKind, Edits},
+    filters::{FilterOption, Filters},
+    find::ListFind,
+    fulltext::{FullTextIndex, TextMatch},
+    help::Help,
+    history::History,
+    index::{Narrowed, SearchIndex, fold},
+    keymap::Keymap,
+    layout::{Pane, PaneLayout},
+    matcher::{FuzzyMatch, MatchKind, regex_keyword, regex_match},
+    mouse::{Areas, Click, ContextMenu},
+    query::{Query, text_spans},
+    suggest::{Suggestion, suggest},
+    theme::Theme,
+};
 
 #[derive(Debug, Clone)]
 pub struct Package {
@@ -17,6 +54,19 @@ pub struct Package {
     pub descipt: String,
     pub name: String,
     pub version: String,
+    pub provides: Vec<String>,
+    pub installed_size: u64, // KiB
+    pub popularity: Option<f64>,
+    // unix timestamps
+    pub install_date: Option<i64>,
+    pub build_date: Option<i64>,
+    pub last_modified: Option<i64>,
+    pub installed_version: Option<String>,
+    pub upgradable: bool,
+    pub explicit: bool,
+    pub orphan: bool,
+    // for aur packages, the repo of the package with the same name
+    pub shadows: Option<String>,
 }
 
 impl Package {
@@ -35,6 +85,17 @@ impl Package {
             descipt,
             name,
             version,
+            provides: Vec::new(),
+            installed_size: 0,
+            popularity: None,
+            install_date: None,
+            build_date: None,
+            last_modified: None,
+            installed_version: None,
+            upgradable: false,
+            explicit: false,
+            orphan: false,
+            shadows: None,
         }
     }
 }
@@ -49,28 +110,340 @@ pub enum ItemRepo {
     AUR(usize),
 }
 
+impl ItemRepo {
+    pub fn name(&self) -> &str {
+        match self {
+            ItemRepo::Core => "core",
+            ItemRepo::Extra => "extra",
+            ItemRepo::Multilib => "multilib",
+            ItemRepo::Archlinuxcn => "archlinuxcn",
+            ItemRepo::AbsOther(string) => string,
+            ItemRepo::AUR(_) => "aur",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct InsertMode {
     pub enabled: bool,
     pub index: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum InfoTab {
+    #[default]
+    Info,
+    Dependencies,
+    Files,
+    History,
+    Pkgbuild,
+    Comments,
+    Updates,
+}
+
+impl InfoTab {
+    pub const ALL: [InfoTab; 7] = [
+        InfoTab::Info,
+        InfoTab::Dependencies,
+        InfoTab::Files,
+        InfoTab::History,
+        InfoTab::Pkgbuild,
+        InfoTab::Comments,
+        InfoTab::Updates,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            InfoTab::Info => "Info",
+            InfoTab::Dependencies => "Dependencies",
+            InfoTab::Files => "Files",
+            InfoTab::History => "History",
+            InfoTab::Pkgbuild => "PKGBUILD",
+            InfoTab::Comments => "Comments",
+            InfoTab::Updates => "Updates",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let i = Self::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Relevance,
+    Name,
+    InstalledSize,
+    DownloadSize,
+    Votes,
+    Popularity,
+    InstallDate,
+    BuildDate,
+    LastModified,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 9] = [
+        SortKey::Relevance,
+        SortKey::Name,
+        SortKey::InstalledSize,
+        SortKey::DownloadSize,
+        SortKey::Votes,
+        SortKey::Popularity,
+        SortKey::InstallDate,
+        SortKey::BuildDate,
+        SortKey::LastModified,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SortKey::Relevance => "relevance",
+            SortKey::Name => "name",
+            SortKey::InstalledSize => "installed size",
+            SortKey::DownloadSize => "download size",
+            SortKey::Votes => "votes",
+            SortKey::Popularity => "popularity",
+            SortKey::InstallDate => "install date",
+            SortKey::BuildDate => "build date",
+            SortKey::LastModified => "last modified",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    // None for packages that have no such value, aur results carry no sizes
+    // and repo packages no votes
+    fn value(self, pkg: &Package) -> Option<f64> {
+        let aur = matches!(pkg.repo, ItemRepo::AUR(_));
+        match self {
+            SortKey::Relevance | SortKey::Name => None,
+            SortKey::InstalledSize => (!aur).then_some(pkg.installed_size as f64),
+            SortKey::DownloadSize => (!aur).then_some(pkg.size as f64),
+            SortKey::Votes => match pkg.repo {
+                ItemRepo::AUR(votes) => Some(votes as f64),
+                _ => None,
+            },
+            SortKey::Popularity => pkg.popularity,
+            SortKey::InstallDate => pkg.install_date.map(|d| d as f64),
+            SortKey::BuildDate => pkg.build_date.map(|d| d as f64),
+            SortKey::LastModified => pkg.last_modified.map(|d| d as f64),
+        }
+    }
+
+    // packages without a value go last in either direction, ties by name
+    pub fn compare(self, a: &Package, b: &Package, descending: bool) -> Ordering {
+        let order = |o: Ordering| if descending { o.reverse() } else { o };
+        let by_key = match self {
+            SortKey::Relevance => Ordering::Equal,
+            SortKey::Name => order(a.name.cmp(&b.name)),
+            key => match (key.value(a), key.value(b)) {
+                (Some(a), Some(b)) => order(a.total_cmp(&b)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+        by_key.then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+// where a row of the package list comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Repo(usize), // index into App::items
+    Aur(usize),  // index into App::aur_results
+    Text(usize), // index into App::text_packages
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub source: Source,
+    // matched char positions of the name, for highlighting
+    pub positions: Vec<usize>,
+    // what a full-text search found
+    pub matched: Option<TextMatch>,
+}
+
 #[derive(Debug)]
 pub struct App {
     pub items: Vec<Package>,
+    // index into items by package name
+    pub by_name: HashMap<String, usize>,
+    // installed packages and their versions
+    pub local: HashMap<String, String>,
+    pub index: SearchIndex,
+    pub narrowed: Option<Narrowed>,
     pub exit: bool,
-    pub filtered: Vec<Package>,
+    // the screen was handed to a helper, the next frame starts from scratch
+    pub redraw: bool,
+    pub filtered: Vec<Hit>,
+    // names of the packages marked for a batch operation
+    pub marked: HashSet<String>,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    // shown instead of the list when a search finds nothing
+    pub suggestions: Vec<Suggestion>,
+    pub sort: SortKey,
+    pub filters: Filters,
+    // the repos present in items, for the filter popup
+    pub repos: Vec<String>,
+    // cursor of the open filter popup
+    pub filter_popup: Option<usize>,
+    pub columns: Columns,
+    // cursor of the open column popup
+    pub column_popup: Option<usize>,
+    pub layout: PaneLayout,
+    pub help: Option<Help>,
+    // takes every key and click while it is open
+    pub dialog: Option<Dialog>,
+    pub find: ListFind,
+    pub context_menu: Option<ContextMenu>,
+    pub areas: Areas,
+    // the last left click on the list, for telling double clicks
+    pub last_click: Option<Click>,
+    pub sort_descending: bool,
+    pub smart_case: bool,
     pub search: String,
+    pub history: History,
+    // the last query that parsed, kept while the search box has a syntax error
+    pub query: Query,
+    pub query_error: Option<String>,
+    pub regex_mode: bool,
+    // compiled from regex_source, kept between keystrokes and while the
+    // pattern being typed does not compile
+    pub regex: Option<Regex>,
+    pub regex_source: (String, bool),
+    pub regex_error: Option<String>,
+    pub fulltext_mode: bool,
+    // loaded on the first switch to full-text mode
+    pub fulltext: Option<FullTextIndex>,
+    // what the full-text search found, aur packages live in text_packages
+    pub text_hits: Vec<(Source, TextMatch)>,
+    pub text_packages: Vec<Package>,
+    // the index and why the aur metadata could not be refreshed
+    pub fulltext_tx: mpsc::UnboundedSender<Result<(FullTextIndex, Option<String>), String>>,
+    pub fulltext_rx: mpsc::UnboundedReceiver<Result<(FullTextIndex, Option<String>), String>>,
+    pub fulltext_task: Option<JoinHandle<()>>,
     pub selected_pack: usize,
     pub list_state: ListState,
     pub selected_win: Window,
     pub insert_mode: InsertMode,
+    pub edits: Edits,
     pub aur_search_block: bool,
-    pub aur_tx: mpsc::UnboundedSender<Vec<Package>>,
-    pub aur_rx: mpsc::UnboundedReceiver<Vec<Package>>,
+    pub aur_tx: mpsc::UnboundedSender<AurResults>,
+    pub aur_rx: mpsc::UnboundedReceiver<AurResults>,
+    pub aur_results: Vec<Package>,
+    pub search_generation: u64,
     pub last_search: String,
-    pub notice: String,
+    pub notice: Notice,
     pub aur_task: Option<JoinHandle<()>>,
+    pub config: Config,
+    pub aur: AurClient,
+    pub info_tab: InfoTab,
+    // every tab keeps its own scroll position
+    pub info_scroll: HashMap<InfoTab, u16>,
+    // the last failed load of each tab
+    pub load_errors: HashMap<InfoTab, LoadError>,
+    pub package_deps: Option<PackageDeps>,
+    pub deps_tx: mpsc::UnboundedSender<Result<PackageDeps, LoadError>>,
+    pub deps_rx: mpsc::UnboundedReceiver<Result<PackageDeps, LoadError>>,
+    pub deps_task: Option<JoinHandle<()>>,
+    pub package_files: Option<PackageFiles>,
+    pub files_tx: mpsc::UnboundedSender<Result<PackageFiles, LoadError>>,
+    pub files_rx: mpsc::UnboundedReceiver<Result<PackageFiles, LoadError>>,
+    pub files_task: Option<JoinHandle<()>>,
+    pub package_history: Option<PackageHistory>,
+    pub history_tx: mpsc::UnboundedSender<Result<PackageHistory, LoadError>>,
+    pub history_rx: mpsc::UnboundedReceiver<Result<PackageHistory, LoadError>>,
+    pub history_task: Option<JoinHandle<()>>,
+    pub aur_source: Option<AurSource>,
+    pub source_tx: mpsc::UnboundedSender<Result<AurSource, LoadError>>,
+    pub source_rx: mpsc::UnboundedReceiver<Result<AurSource, LoadError>>,
+    pub source_task: Option<JoinHandle<()>>,
+    pub aur_comments: Option<AurComments>,
+    pub comments_tx: mpsc::UnboundedSender<Result<AurComments, LoadError>>,
+    pub comments_rx: mpsc::UnboundedReceiver<Result<AurComments, LoadError>>,
+    pub comments_task: Option<JoinHandle<()>>,
+    pub aur_updates: Option<UpdateReport>,
+    pub updates_tx: mpsc::UnboundedSender<Result<UpdateReport, String>>,
+    pub updates_rx: mpsc::UnboundedReceiver<Result<UpdateReport, String>>,
+    pub updates_task: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoticeLevel {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+// a load of an info tab that failed, kept so the tab shows it for that
+// package instead of fetching it again on every key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    pub name: String,
+    pub message: String,
+}
+
+impl LoadError {
+    pub fn new(name: &str, error: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+// the message line below the info pane
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Notice {
+    pub text: String,
+    pub level: NoticeLevel,
+}
+
+impl Notice {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level: NoticeLevel::Info,
+        }
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level: NoticeLevel::Warning,
+        }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level: NoticeLevel::Error,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// the aur answer to the search with the same generation
+#[derive(Debug)]
+pub struct AurResults {
+    pub generation: u64,
+    pub pkgs: Result<Vec<Package>, String>,
 }
 
 #[derive(Debug, Clone)]
@@ -82,18 +455,99 @@ pub enum Window {
 
 impl App {
     pub fn new() -> Result<Self, Box<dyn Error>> {
-        let all_packages = load_repo_packages()?;
+        let config = Config::load()?;
+        let keymap = Keymap::new(&config.keys)?;
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let theme = Theme::new(&config.theme, no_color)?;
+        let mut app = Self::with_packages(load_repo_packages()?, config);
+        app.keymap = keymap;
+        app.theme = theme;
+        app.local.extend(load_foreign_packages()?);
+        app.filters = Filters::load();
+        app.layout = PaneLayout::load(&app.config.layout);
+        app.history = History::load(app.config.history_size);
+        app.merge_results();
+        Ok(app)
+    }
+
+    pub fn with_packages(all_packages: Vec<Package>, config: Config) -> Self {
+        let mut repos: Vec<String> = all_packages
+            .iter()
+            .map(|p| p.repo.name().to_string())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        repos.sort();
         let mut list_state = ListState::default();
         list_state.select(Some(0));
         let (aur_tx, aur_rx) = mpsc::unbounded_channel();
-        let app = Self {
-            filtered: all_packages.clone(),
+        let (source_tx, source_rx) = mpsc::unbounded_channel();
+        let (comments_tx, comments_rx) = mpsc::unbounded_channel();
+        let (updates_tx, updates_rx) = mpsc::unbounded_channel();
+        let (fulltext_tx, fulltext_rx) = mpsc::unbounded_channel();
+        let (deps_tx, deps_rx) = mpsc::unbounded_channel();
+        let (files_tx, files_rx) = mpsc::unbounded_channel();
+        let (history_tx, history_rx) = mpsc::unbounded_channel();
+        Self {
+            filtered: (0..all_packages.len())
+                .map(|i| Hit {
+                    source: Source::Repo(i),
+                    positions: Vec::new(),
+                    matched: None,
+                })
+                .collect(),
+            suggestions: Vec::new(),
+            marked: HashSet::new(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            sort: SortKey::default(),
+            filters: Filters::default(),
+            repos,
+            filter_popup: None,
+            columns: Columns::new(&config.columns),
+            column_popup: None,
+            layout: PaneLayout::new(&config.layout),
+            help: None,
+            dialog: None,
+            find: ListFind::default(),
+            context_menu: None,
+            areas: Areas::default(),
+            last_click: None,
+            sort_descending: false,
+            by_name: all_packages
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.name.clone(), i))
+                .collect(),
+            local: all_packages
+                .iter()
+                .filter_map(|p| Some((p.name.clone(), p.installed_version.clone()?)))
+                .collect(),
+            index: SearchIndex::new(&all_packages),
+            narrowed: None,
+            smart_case: true,
             exit: false,
+            redraw: false,
             search: String::new(),
+            history: History::new(Vec::new(), config.history_size, None),
+            query: Query::default(),
+            query_error: None,
+            regex_mode: false,
+            regex: None,
+            regex_source: (String::new(), false),
+            regex_error: None,
+            fulltext_mode: false,
+            fulltext: None,
+            text_hits: Vec::new(),
+            text_packages: Vec::new(),
+            fulltext_tx,
+            fulltext_rx,
+            fulltext_task: None,
             selected_pack: 0,
             items: all_packages,
             list_state,
             selected_win: Window::Search,
+            edits: Edits::default(),
             insert_mode: InsertMode {
                 enabled: false,
                 index: 0,
@@ -101,37 +555,749 @@ impl App {
             aur_search_block: false,
             aur_tx,
             aur_rx,
+            aur_results: Vec::new(),
+            search_generation: 0,
             last_search: String::new(),
-            notice: String::new(),
+            notice: Notice::default(),
             aur_task: None,
-        };
-        Ok(app)
+            aur: config.aur_client(),
+            config,
+            info_tab: InfoTab::default(),
+            info_scroll: HashMap::new(),
+            load_errors: HashMap::new(),
+            package_deps: None,
+            deps_tx,
+            deps_rx,
+            deps_task: None,
+            package_files: None,
+            files_tx,
+            files_rx,
+            files_task: None,
+            package_history: None,
+            history_tx,
+            history_rx,
+            history_task: None,
+            aur_source: None,
+            source_tx,
+            source_rx,
+            source_task: None,
+            aur_comments: None,
+            comments_tx,
+            comments_rx,
+            comments_task: None,
+            aur_updates: None,
+            updates_tx,
+            updates_rx,
+            updates_task: None,
+        }
     }
 
     pub fn update_filter_local(&mut self) {
-        self.filtered = self
-            .items
-            .iter()
-            .filter(|p| p.name.contains(&self.search))
-            .cloned()
+        // the full-text index covers the aur itself, no rpc search needed
+        let keyword = if self.fulltext_mode {
+            self.update_text_hits();
+            String::new()
+        } else if self.regex_mode {
+            self.update_regex();
+            regex_keyword(&self.search)
+        } else {
+            self.update_query();
+            self.query.aur_keyword()
+        };
+        if keyword != self.last_search {
+            self.last_search = keyword;
+            self.update_aur();
+        }
+        self.merge_results();
+    }
+
+    fn update_query(&mut self) {
+        match Query::parse(&self.search) {
+            Ok(query) => {
+                if self.query_error.take().is_some() {
+                    self.notice.clear();
+                }
+                self.query = query;
+            }
+            Err(e) => {
+                self.notice = Notice::error(e.to_string());
+                self.query_error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn update_regex(&mut self) {
+        let ignore_case = self.smart_case && !self.search.chars().any(char::is_uppercase);
+        let source = (self.search.clone(), ignore_case);
+        if source == self.regex_source && (self.regex.is_some() || self.regex_error.is_some()) {
+            return;
+        }
+        match RegexBuilder::new(&self.search)
+            .case_insensitive(ignore_case)
+            .build()
+        {
+            Ok(re) => {
+                self.regex = Some(re);
+                self.regex_error = None;
+            }
+            Err(e) => {
+                let msg = e.to_string();
+                // the last line of the regex error is the short description
+                self.regex_error = Some(msg.lines().last().unwrap_or(&msg).trim().to_string());
+            }
+        }
+        self.regex_source = source;
+    }
+
+    pub fn toggle_regex_mode(&mut self) {
+        self.regex_mode = !self.regex_mode;
+        self.fulltext_mode = false;
+        self.regex_error = None;
+        self.regex = None;
+        self.notice = Notice::info(match self.regex_mode {
+            true => "regex search".to_string(),
+            false => "fuzzy search".to_string(),
+        });
+        self.update_filter_local();
+    }
+
+    fn rank(&self, pkg: &Package) -> Option<FuzzyMatch> {
+        if !self.regex_mode {
+            return self.query.rank(pkg, self.smart_case);
+        }
+        match &self.regex {
+            Some(re) => regex_match(re, &pkg.name),
+            None => Some(FuzzyMatch {
+                kind: MatchKind::Subsequence,
+                score: 0,
+                positions: Vec::new(),
+            }),
+        }
+    }
+
+    // repo matches and aur results of the current query are ranked by fuzzy
+    // score, and the selected package stays selected. the aur results are
+    // filtered by the query client side as well
+    pub fn merge_results(&mut self) {
+        let selected = self.selected_package().map(|p| p.name.clone());
+        self.merge_keeping(selected);
+    }
+
+    fn merge_keeping(&mut self, selected: Option<String>) {
+        let mut filtered = match self.fulltext_mode {
+            true => self.text_results(),
+            false => self.ranked_results(),
+        };
+        match self.sort {
+            SortKey::Relevance if self.sort_descending => filtered.reverse(),
+            SortKey::Relevance => {}
+            key => filtered.sort_by(|a, b| {
+                key.compare(
+                    self.package(a.source),
+                    self.package(b.source),
+                    self.sort_descending,
+                )
+            }),
+        }
+        if self.config.section_headers {
+            filtered.sort_by_key(|hit| matches!(self.package(hit.source).repo, ItemRepo::AUR(_)));
+        }
+        self.filtered = filtered;
+        self.after_merge(selected);
+    }
+
+    fn ranked_results(&mut self) -> Vec<Hit> {
+        let mut hits = self.repo_hits();
+        hits.retain(|(i, _)| self.filters.allows(&self.items[*i]));
+
+        let mut seen = HashSet::new();
+        let mut aur: Vec<usize> = (0..self.aur_results.len())
+            .filter(|&i| seen.insert(self.aur_results[i].name.as_str()))
             .collect();
+        aur.sort_by(|&a, &b| self.aur_results[a].name.cmp(&self.aur_results[b].name));
+        aur.retain(|&i| self.filters.allows(&self.aur_results[i]));
+        let aur_hits = aur
+            .into_iter()
+            .filter_map(|i| self.rank(&self.aur_results[i]).map(|m| (Source::Aur(i), m)));
+
+        // repo and aur results are ranked together, a repo package comes
+        // before an aur one of the same name
+        let mut ranked: Vec<(Source, FuzzyMatch)> = hits
+            .into_iter()
+            .map(|(i, m)| (Source::Repo(i), m))
+            .chain(aur_hits)
+            .collect();
+        if !self.regex_mode && self.query.has_text() {
+            ranked.sort_by(|a, b| {
+                FuzzyMatch::rank(
+                    (&a.1, &self.package(a.0).name),
+                    (&b.1, &self.package(b.0).name),
+                )
+            });
+        }
+        ranked
+            .into_iter()
+            .map(|(source, m)| Hit {
+                source,
+                positions: m.positions,
+                matched: None,
+            })
+            .collect()
+    }
+
+    fn text_results(&self) -> Vec<Hit> {
+        self.text_hits
+            .iter()
+            .filter(|(source, _)| self.filters.allows(self.package(*source)))
+            .map(|(source, matched)| Hit {
+                source: *source,
+                positions: Vec::new(),
+                matched: Some(matched.clone()),
+            })
+            .collect()
+    }
+
+    // full-text hits of repo packages point into items, aur ones get a
+    // package of their own
+    fn update_text_hits(&mut self) {
+        self.text_hits.clear();
+        self.text_packages.clear();
+        let Some(index) = &self.fulltext else {
+            return;
+        };
+        for hit in index.search(&self.search) {
+            let doc = &index.docs[hit.doc];
+            let source = match self.by_name.get(&doc.name) {
+                Some(&i) if doc.repo != "aur" => Source::Repo(i),
+                _ => {
+                    let mut pkg = Package::new(
+                        false,
+                        ItemRepo::AUR(doc.votes.unwrap_or(0) as usize),
+                        0,
+                        doc.description.clone(),
+                        doc.name.clone(),
+                        doc.version.clone(),
+                    );
+                    pkg.provides = doc.provides.clone();
+                    pkg.popularity = doc.popularity;
+                    pkg.installed_version = self.local.get(&doc.name).cloned();
+                    pkg.is_installed = pkg.installed_version.is_some();
+                    pkg.shadows = self
+                        .by_name
+                        .get(&doc.name)
+                        .map(|&i| self.items[i].repo.name().to_string());
+                    self.text_packages.push(pkg);
+                    Source::Text(self.text_packages.len() - 1)
+                }
+            };
+            self.text_hits.push((source, hit.matched));
+        }
+    }
+
+    pub fn toggle_fulltext_mode(&mut self) {
+        self.fulltext_mode = !self.fulltext_mode;
+        self.regex_mode = false;
+        self.notice = Notice::info(match self.fulltext_mode {
+            true => "full-text search over names, descriptions, provides and files".to_string(),
+            false => "fuzzy search".to_string(),
+        });
+        if self.fulltext_mode && self.fulltext.is_none() && self.fulltext_task.is_none() {
+            let tx = self.fulltext_tx.clone();
+            let client = self.aur.clone();
+            self.notice = Notice::info("loading the full-text index");
+            self.fulltext_task = Some(tokio::spawn(async move {
+                let index = open_index(&client).await.map_err(|e| e.to_string());
+                tx.send(index).unwrap_or_default();
+            }));
+        }
+        self.update_filter_local();
+    }
+
+    pub fn apply_fulltext_index(&mut self, index: FullTextIndex, refresh_error: Option<String>) {
+        let aur = index.docs.iter().filter(|d| d.repo == "aur").count();
+        let indexed = format!(
+            "{} packages indexed, {} of them from the aur",
+            index.docs.len(),
+            aur
+        );
+        self.notice = match refresh_error {
+            Some(e) => Notice::error(format!(
+                "{}, refreshing the aur metadata failed: {}",
+                indexed, e
+            )),
+            None => Notice::info(indexed),
+        };
+        self.fulltext = Some(index);
+        self.fulltext_task = None;
+        self.update_filter_local();
+    }
+
+    fn after_merge(&mut self, selected: Option<String>) {
+        let index = selected
+            .and_then(|name| self.filtered_packages().position(|p| p.name == name))
+            .or((!self.filtered.is_empty()).then_some(0));
+        self.list_state.select(index);
         if self.selected_pack >= self.filtered.len() {
             self.selected_pack = 0;
         }
+        self.update_suggestions();
+    }
+
+    // the term suggestions are made for, the one the aur gets searched with
+    fn suggestion_term(&self) -> Option<String> {
+        self.query
+            .text_terms()
+            .into_iter()
+            .max_by_key(|t| t.chars().count())
+            .map(str::to_string)
+    }
+
+    // only once the aur had its say, the list takes the suggestions' place
+    fn update_suggestions(&mut self) {
+        self.suggestions.clear();
+        if !self.filtered.is_empty()
+            || self.regex_mode
+            || self.fulltext_mode
+            || self.aur_search_block
+        {
+            return;
+        }
+        let Some(term) = self.suggestion_term() else {
+            return;
+        };
+        self.suggestions = suggest(&self.items, &term);
+        if !self.suggestions.is_empty() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    pub fn accept_suggestion(&mut self, index: usize) {
+        let (Some(suggestion), Some(term)) = (self.suggestions.get(index), self.suggestion_term())
+        else {
+            return;
+        };
+        // the word the term was typed as, the same text elsewhere in the
+        // search stays as it is
+        let Some((_, span)) = text_spans(&self.search)
+            .into_iter()
+            .rfind(|(text, _)| *text == term)
+        else {
+            return;
+        };
+        let before = self.search.chars().take(span.start);
+        let after = self.search.chars().skip(span.end);
+        let search = before
+            .chain(suggestion.term().chars())
+            .chain(after)
+            .collect();
+        self.notice = Notice::info(format!("searching for {} instead", suggestion.name));
+        self.set_search(search);
+        self.update_filter_local();
+    }
+
+    // ranks the repo packages, only looking at the matches of the previous
+    // query when the current one narrows it and skipping names the search
+    // index rules out
+    fn repo_hits(&mut self) -> Vec<(usize, FuzzyMatch)> {
+        if self.regex_mode {
+            self.narrowed = None;
+            return (0..self.items.len())
+                .filter_map(|i| self.rank(&self.items[i]).map(|m| (i, m)))
+                .collect();
+        }
+
+        let candidates = match self.narrowed.take() {
+            Some(n) if n.smart_case == self.smart_case && self.query.narrows(&n.query) => n.hits,
+            _ => (0..self.items.len()).collect(),
+        };
+        let terms: Vec<String> = self.query.required_text().into_iter().map(fold).collect();
+        let hits: Vec<(usize, FuzzyMatch)> = candidates
+            .into_iter()
+            .filter(|&i| self.index.may_match(i, &terms))
+            .filter_map(|i| self.rank(&self.items[i]).map(|m| (i, m)))
+            .collect();
+
+        self.narrowed = Some(Narrowed {
+            query: self.query.clone(),
+            smart_case: self.smart_case,
+            hits: hits.iter().map(|(i, _)| *i).collect(),
+        });
+        hits
+    }
+
+    pub fn package(&self, source: Source) -> &Package {
+        match source {
+            Source::Repo(i) => &self.items[i],
+            Source::Aur(i) => &self.aur_results[i],
+            Source::Text(i) => &self.text_packages[i],
+        }
+    }
+
+    pub fn package_mut(&mut self, source: Source) -> &mut Package {
+        // the installed state takes part in matching
+        self.narrowed = None;
+        match source {
+            Source::Repo(i) => &mut self.items[i],
+            Source::Aur(i) => &mut self.aur_results[i],
+            Source::Text(i) => &mut self.text_packages[i],
+        }
+    }
 
-        self.update_aur();
+    pub fn filtered_packages(&self) -> impl Iterator<Item = &Package> {
+        self.filtered.iter().map(|hit| self.package(hit.source))
     }
 
+    // every query change starts a new generation, answers of older ones are dropped
     pub fn update_aur(&mut self) {
         if let Some(task) = self.aur_task.take() {
             task.abort();
         }
-        let keyword = self.search.clone();
+        self.search_generation += 1;
+        // rows of the old results must not point into the new ones
+        self.filtered
+            .retain(|hit| matches!(hit.source, Source::Repo(_)));
+        self.aur_results.clear();
+        self.aur_search_block = !self.last_search.is_empty();
+        if self.last_search.is_empty() {
+            return;
+        }
+
+        let generation = self.search_generation;
+        let keyword = self.last_search.clone();
         let tx = self.aur_tx.clone();
+        let client = self.aur.clone();
         self.aur_task = Some(tokio::spawn(async move {
             sleep(Duration::from_millis(300)).await;
-            let pkgs = get_aur_packages(keyword).await.unwrap_or_default();
-            tx.send(pkgs).unwrap_or_default();
+            let pkgs = get_aur_packages(&client, &keyword)
+                .await
+                .map_err(|e| e.to_string());
+            tx.send(AurResults { generation, pkgs }).unwrap_or_default();
+        }));
+    }
+
+    pub fn apply_aur_results(&mut self, results: AurResults) -> bool {
+        if results.generation != self.search_generation {
+            return false;
+        }
+        self.aur_search_block = false;
+        match results.pkgs {
+            Ok(pkgs) => {
+                if pkgs.is_empty() {
+                    self.notice =
+                        Notice::warning(format!("no aur results for {}", self.last_search));
+                }
+                let selected = self.selected_package().map(|p| p.name.clone());
+                // rows of the old results must not point into the new ones
+                self.filtered
+                    .retain(|hit| matches!(hit.source, Source::Repo(_)));
+                self.aur_results = pkgs;
+                self.mark_local_aur_packages();
+                self.merge_keeping(selected);
+            }
+            Err(e) => {
+                self.notice = Notice::error(format!("aur searching error: {}", e));
+                self.update_suggestions();
+            }
+        }
+        true
+    }
+
+    // replaces the search box content, with the cursor at the end. walking
+    // the history is one undo step back to what was there before
+    pub fn set_search(&mut self, search: String) {
+        if search != self.search {
+            self.edits
+                .record(EditKind::Replace, &self.search, self.insert_mode.index);
+        }
+        self.insert_mode.index = search.chars().count();
+        self.search = search;
+    }
+
+    pub fn submit_search(&mut self) {
+        if let Err(e) = self.history.push(&self.search) {
+            self.notice = Notice::error(format!("failed to save the search history: {}", e));
+        }
+    }
+
+    pub fn filter_options(&self) -> Vec<FilterOption> {
+        FilterOption::all(&self.repos)
+    }
+
+    pub fn toggle_filter(&mut self, index: usize) {
+        let Some(option) = self.filter_options().into_iter().nth(index) else {
+            return;
+        };
+        self.filters.toggle(&option);
+        if let Err(e) = self.filters.save() {
+            self.notice = Notice::error(format!("failed to save the filters: {}", e));
+        }
+        self.merge_results();
+    }
+
+    // grows the list by steps of the split, shrinks it for negative ones
+    pub fn resize_list(&mut self, steps: i32) {
+        self.layout.maximized = None;
+        self.layout.resize(steps);
+        self.notice = Notice::info(format!("list at {}%", self.layout.split));
+        self.save_layout();
+    }
+
+    pub fn toggle_maximized(&mut self, pane: Pane) {
+        self.layout.toggle_maximized(pane);
+    }
+
+    pub fn reset_layout(&mut self) {
+        self.layout = PaneLayout::new(&self.config.layout);
+        if let Err(e) = PaneLayout::remove_saved() {
+            self.notice = Notice::error(format!("failed to reset the layout: {}", e));
+        }
+    }
+
+    fn save_layout(&mut self) {
+        if let Err(e) = self.layout.save() {
+            self.notice = Notice::error(format!("failed to save the layout: {}", e));
+        }
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.notice = Notice::info(format!("sorted by {}", self.sort.title()));
+        self.merge_results();
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.merge_results();
+    }
+
+    // the aur rpc knows nothing about this system, the installed state comes
+    // from the local db and a name shared with a repo package gets flagged.
+    // an installed package of that name is the repo one, not the aur one
+    pub fn mark_local_aur_packages(&mut self) {
+        let updates = self.aur_updates.as_ref().map(|r| &r.updates);
+        for pkg in &mut self.aur_results {
+            pkg.shadows = self
+                .by_name
+                .get(&pkg.name)
+                .map(|&i| self.items[i].repo.name().to_string());
+            pkg.installed_version = match pkg.shadows {
+                Some(_) => None,
+                None => self.local.get(&pkg.name).cloned(),
+            };
+            pkg.is_installed = pkg.installed_version.is_some();
+            pkg.upgradable = updates.is_some_and(|u| u.iter().any(|u| u.name == pkg.name));
+        }
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let Some(name) = self.selected_package().map(|p| p.name.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&name) {
+            self.marked.insert(name);
+        }
+        self.notice = Notice::info(format!("{} packages marked", self.marked.len()));
+    }
+
+    pub fn toggle_smart_case(&mut self) {
+        self.smart_case = !self.smart_case;
+        self.notice = Notice::info(match self.smart_case {
+            true => "smart case matching".to_string(),
+            false => "case sensitive matching".to_string(),
+        });
+        self.update_filter_local();
+    }
+
+    pub fn selected_package(&self) -> Option<&Package> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.filtered.get(i))
+            .map(|hit| self.package(hit.source))
+    }
+
+    fn selected_aur_name(&self) -> Option<String> {
+        self.selected_package()
+            .filter(|p| matches!(p.repo, ItemRepo::AUR(_)))
+            .map(|p| p.name.clone())
+    }
+
+    pub fn update_aur_source(&mut self) {
+        let Some(name) = self.selected_aur_name() else {
+            return;
+        };
+        if self.aur_source.as_ref().is_some_and(|s| s.name == name)
+            || self.load_error(InfoTab::Pkgbuild, &name).is_some()
+        {
+            return;
+        }
+        if let Some(task) = self.source_task.take() {
+            task.abort();
+        }
+        let tx = self.source_tx.clone();
+        let client = self.aur.clone();
+        self.notice = Notice::info(format!("fetching the sources of {}", name));
+        self.source_task = Some(tokio::spawn(async move {
+            let source = fetch_aur_source(&client, name.clone(), &pkgbuild::cache_dir())
+                .await
+                .map_err(|e| LoadError::new(&name, e));
+            tx.send(source).unwrap_or_default();
+        }));
+    }
+
+    pub fn update_aur_comments(&mut self) {
+        let Some(name) = self.selected_aur_name() else {
+            return;
+        };
+        if self.aur_comments.as_ref().is_some_and(|c| c.name == name)
+            || self.load_error(InfoTab::Comments, &name).is_some()
+        {
+            return;
+        }
+        if let Some(task) = self.comments_task.take() {
+            task.abort();
+        }
+        let tx = self.comments_tx.clone();
+        let client = self.aur.clone();
+        self.comments_task = Some(tokio::spawn(async move {
+            let comments = fetch_aur_comments(&client, name.clone())
+                .await
+                .map_err(|e| LoadError::new(&name, e));
+            tx.send(comments).unwrap_or_default();
+        }));
+    }
+
+    pub fn load_error(&self, tab: InfoTab, name: &str) -> Option<&str> {
+        self.load_errors
+            .get(&tab)
+            .filter(|e| e.name == name)
+            .map(|e| e.message.as_str())
+    }
+
+    pub fn load_failed(&mut self, tab: InfoTab, error: LoadError) {
+        self.notice = Notice::error(error.message.clone());
+        self.load_errors.insert(tab, error);
+    }
+
+    // what a tab's task sent back, or nothing when the selection has moved on
+    // since, the tab fetches again once that package is selected
+    pub fn take_loaded<T>(
+        &mut self,
+        tab: InfoTab,
+        loaded: Result<T, LoadError>,
+        name: impl Fn(&T) -> &str,
+    ) -> Option<T> {
+        let selected = |n: &str| self.selected_package().is_some_and(|p| p.name == n);
+        match loaded {
+            Ok(data) if selected(name(&data)) => {
+                self.info_scroll.remove(&tab);
+                Some(data)
+            }
+            Err(e) if selected(&e.name) => {
+                self.load_failed(tab, e);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    // fetches what the open tab shows unless it has it for the selection
+    pub fn load_info_tab(&mut self) {
+        match self.info_tab {
+            InfoTab::Dependencies => self.update_package_deps(),
+            InfoTab::Files => self.update_package_files(),
+            InfoTab::History => self.update_package_history(),
+            InfoTab::Pkgbuild => self.update_aur_source(),
+            InfoTab::Comments => self.update_aur_comments(),
+            InfoTab::Info | InfoTab::Updates => {}
+        }
+    }
+
+    pub fn info_scroll(&self, tab: InfoTab) -> u16 {
+        self.info_scroll.get(&tab).copied().unwrap_or(0)
+    }
+
+    pub fn scroll_info(&mut self, down: bool) {
+        let scroll = self.info_scroll.entry(self.info_tab).or_default();
+        *scroll = match down {
+            true => scroll.saturating_add(1),
+            false => scroll.saturating_sub(1),
+        };
+    }
+
+    pub fn update_package_deps(&mut self) {
+        let Some(pkg) = self.selected_package() else {
+            return;
+        };
+        let (name, aur) = (pkg.name.clone(), matches!(pkg.repo, ItemRepo::AUR(_)));
+        if self.package_deps.as_ref().is_some_and(|d| d.name == name)
+            || self.load_error(InfoTab::Dependencies, &name).is_some()
+        {
+            return;
+        }
+        if let Some(task) = self.deps_task.take() {
+            task.abort();
+        }
+        let tx = self.deps_tx.clone();
+        let client = self.aur.clone();
+        self.deps_task = Some(tokio::spawn(async move {
+            let deps = fetch_dependencies(&client, name.clone(), aur)
+                .await
+                .map_err(|e| LoadError::new(&name, e));
+            tx.send(deps).unwrap_or_default();
+        }));
+    }
+
+    pub fn update_package_files(&mut self) {
+        let Some(name) = self.selected_package().map(|p| p.name.clone()) else {
+            return;
+        };
+        if self.package_files.as_ref().is_some_and(|f| f.name == name)
+            || self.load_error(InfoTab::Files, &name).is_some()
+        {
+            return;
+        }
+        if let Some(task) = self.files_task.take() {
+            task.abort();
+        }
+        let tx = self.files_tx.clone();
+        self.files_task = Some(tokio::task::spawn_blocking(move || {
+            let files = load_files(&name).map_err(|e| LoadError::new(&name, e));
+            tx.send(files).unwrap_or_default();
+        }));
+    }
+
+    pub fn update_package_history(&mut self) {
+        let Some(name) = self.selected_package().map(|p| p.name.clone()) else {
+            return;
+        };
+        if self
+            .package_history
+            .as_ref()
+            .is_some_and(|h| h.name == name)
+            || self.load_error(InfoTab::History, &name).is_some()
+        {
+            return;
+        }
+        if let Some(task) = self.history_task.take() {
+            task.abort();
+        }
+        let tx = self.history_tx.clone();
+        self.history_task = Some(tokio::task::spawn_blocking(move || {
+            let history = load_history(&name).map_err(|e| LoadError::new(&name, e));
+            tx.send(history).unwrap_or_default();
+        }));
+    }
+
+    pub fn check_updates(&mut self) {
+        if let Some(task) = self.updates_task.take() {
+            task.abort();
+        }
+        let tx = self.updates_tx.clone();
+        let client = self.aur.clone();
+        self.notice = Notice::info("checking installed foreign packages against the aur");
+        self.updates_task = Some(tokio::spawn(async move {
+            let report = check_aur_updates(&client).await.map_err(|e| e.to_string());
+            tx.send(report).unwrap_or_default();
         }));
     }
 }
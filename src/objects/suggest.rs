@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use crate::objects::stat::Package;
+
+pub const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub name: String,
+    // a provides name of some package rather than a package name
+    pub provided: bool,
+    pub distance: usize,
+}
+
+impl Suggestion {
+    // what replaces the misspelled term in the search box
+    pub fn term(&self) -> String {
+        match self.provided {
+            true => format!("provides:{}", self.name),
+            false => self.name.clone(),
+        }
+    }
+}
+
+// the closest package and provides names to `word`, package names win ties
+pub fn suggest(items: &[Package], word: &str) -> Vec<Suggestion> {
+    let word: Vec<char> = word.to_lowercase().chars().collect();
+    let max = match word.len() {
+        0..=2 => return Vec::new(),
+        3..=4 => 1,
+        5..=8 => 2,
+        _ => 3,
+    };
+
+    let names = items.iter().map(|p| (p.name.as_str(), false));
+    let provides = items
+        .iter()
+        .flat_map(|p| p.provides.iter().map(|name| (name.as_str(), true)));
+    let mut seen = HashSet::new();
+    let mut found: Vec<Suggestion> = names
+        .chain(provides)
+        .filter(|(name, _)| seen.insert(*name))
+        .filter_map(|(name, provided)| {
+            let distance = edit_distance(&word, name, max)?;
+            Some(Suggestion {
+                name: name.to_string(),
+                provided,
+                distance,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        (a.distance, a.provided, a.name.len(), &a.name).cmp(&(
+            b.distance,
+            b.provided,
+            b.name.len(),
+            &b.name,
+        ))
+    });
+    found.truncate(MAX_SUGGESTIONS);
+    found
+}
+
+// optimal string alignment distance between the lowercase `word` and
+// `name`, so `fierfox` is one swap from `firefox`. None when it exceeds `max`
+pub fn edit_distance(word: &[char], name: &str, max: usize) -> Option<usize> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    if name.len().abs_diff(word.len()) > max {
+        return None;
+    }
+    let width = name.len() + 1;
+    let mut rows = vec![vec![0; width]; word.len() + 1];
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=word.len() {
+        rows[i][0] = i;
+        for j in 1..width {
+            let cost = usize::from(word[i - 1] != name[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && word[i - 1] == name[j - 2] && word[i - 2] == name[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+        if rows[i].iter().min().is_some_and(|&m| m > max) {
+            return None;
+        }
+    }
+    Some(rows[word.len()][name.len()]).filter(|&d| d <= max)
+}
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, str::FromStr};
+
+use ratatui::style::{Color, Modifier, Style, palette::tailwind::SLATE};
+use serde::Deserialize;
+
+use crate::objects::stat::NoticeLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+// one element in the `[theme]` table, e.g. `selected = { bg = "#334155", bold = true }`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub dim: Option<bool>,
+    pub reversed: Option<bool>,
+}
+
+// a built-in theme to start from and the elements changed on top of it,
+// repo tags are set with `"repo.<name>"`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub base: ThemeName,
+    #[serde(flatten)]
+    pub styles: HashMap<String, StyleConfig>,
+}
+
+// the styles of the PKGBUILD highlighter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Syntax {
+    pub comment: Style,
+    pub string: Style,
+    pub variable: Style,
+    pub keyword: Style,
+    pub assign: Style,
+    pub function: Style,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub text: Style,
+    // secondary text: dates, paths, hints
+    pub dim: Style,
+    // headings and field names in the info pane
+    pub label: Style,
+    pub border: Style,
+    pub selected: Style,
+    // matched characters of a name
+    pub matched: Style,
+    pub marked: Style,
+    pub installed: Style,
+    // an aur package sharing its name with a repo one
+    pub shadowed: Style,
+    // the field a full-text search matched
+    pub text_match: Style,
+    pub chip: Style,
+    pub section_header: Style,
+    pub search: Style,
+    pub search_insert: Style,
+    pub search_error: Style,
+    pub notice_info: Style,
+    pub notice_warning: Style,
+    pub notice_error: Style,
+    // new versions, installs and the like, and their opposites
+    pub added: Style,
+    pub removed: Style,
+    pub flagged: Style,
+    // the repo tag of a list row, per repo with a fallback
+    pub repo: Style,
+    pub repos: HashMap<String, Style>,
+    pub syntax: Syntax,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            text: Style::new().fg(SLATE.c200),
+            dim: Style::new().fg(Color::DarkGray),
+            label: Style::new().add_modifier(Modifier::BOLD),
+            border: Style::new().fg(Color::LightBlue),
+            selected: Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD),
+            matched: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            marked: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            installed: Style::new().fg(Color::Green),
+            shadowed: Style::new().fg(Color::Red),
+            text_match: Style::new().fg(SLATE.c500),
+            chip: Style::new().fg(SLATE.c950).bg(Color::LightBlue),
+            section_header: Style::new()
+                .fg(Color::LightBlue)
+                .add_modifier(Modifier::BOLD),
+            search: Style::new().fg(Color::Yellow),
+            search_insert: Style::new(),
+            search_error: Style::new().fg(Color::Red),
+            notice_info: Style::new().fg(Color::LightBlue),
+            notice_warning: Style::new().fg(Color::Yellow),
+            notice_error: Style::new().fg(Color::Red),
+            added: Style::new().fg(Color::Green),
+            removed: Style::new().fg(Color::Red),
+            flagged: Style::new().fg(Color::Yellow),
+            repo: Style::new(),
+            repos: HashMap::from([
+                ("core".to_string(), Style::new().fg(Color::LightRed)),
+                ("extra".to_string(), Style::new().fg(Color::LightGreen)),
+                ("multilib".to_string(), Style::new().fg(Color::LightCyan)),
+                ("aur".to_string(), Style::new().fg(Color::LightMagenta)),
+            ]),
+            syntax: Syntax {
+                comment: Style::new().fg(Color::DarkGray),
+                string: Style::new().fg(Color::Green),
+                variable: Style::new().fg(Color::Cyan),
+                keyword: Style::new().fg(Color::Magenta),
+                assign: Style::new().fg(Color::Yellow),
+                function: Style::new().fg(Color::Blue),
+            },
+        }
+    }
+
+    // for terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            text: Style::new().fg(SLATE.c800),
+            dim: Style::new().fg(SLATE.c500),
+            border: Style::new().fg(Color::Blue),
+            selected: Style::new().bg(SLATE.c200).add_modifier(Modifier::BOLD),
+            matched: Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            marked: Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            installed: Style::new().fg(Color::Green),
+            text_match: Style::new().fg(SLATE.c500),
+            chip: Style::new().fg(Color::White).bg(Color::Blue),
+            section_header: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            search: Style::new().fg(Color::Blue),
+            notice_info: Style::new().fg(Color::Blue),
+            notice_warning: Style::new().fg(Color::Rgb(0xb4, 0x53, 0x09)),
+            flagged: Style::new().fg(Color::Rgb(0xb4, 0x53, 0x09)),
+            repos: HashMap::from([
+                ("core".to_string(), Style::new().fg(Color::Red)),
+                ("extra".to_string(), Style::new().fg(Color::Green)),
+                ("multilib".to_string(), Style::new().fg(Color::Cyan)),
+                ("aur".to_string(), Style::new().fg(Color::Magenta)),
+            ]),
+            syntax: Syntax {
+                comment: Style::new().fg(SLATE.c500),
+                assign: Style::new().fg(Color::Rgb(0xb4, 0x53, 0x09)),
+                ..Self::dark().syntax
+            },
+            ..Self::dark()
+        }
+    }
+
+    // plain black and white with bright accents, emphasis by modifiers
+    pub fn high_contrast() -> Self {
+        let bold = |color: Color| Style::new().fg(color).add_modifier(Modifier::BOLD);
+        Self {
+            text: Style::new().fg(Color::White),
+            dim: Style::new().fg(Color::Gray),
+            border: Style::new().fg(Color::White),
+            selected: Style::new()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            matched: bold(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+            marked: bold(Color::LightYellow),
+            installed: bold(Color::LightGreen),
+            shadowed: bold(Color::LightRed),
+            text_match: Style::new().fg(Color::Gray),
+            chip: Style::new().fg(Color::Black).bg(Color::LightYellow),
+            section_header: bold(Color::White).add_modifier(Modifier::UNDERLINED),
+            search: bold(Color::LightYellow),
+            search_insert: bold(Color::White),
+            search_error: bold(Color::LightRed),
+            notice_info: Style::new().fg(Color::White),
+            notice_warning: bold(Color::LightYellow),
+            notice_error: bold(Color::LightRed),
+            added: bold(Color::LightGreen),
+            removed: bold(Color::LightRed),
+            flagged: bold(Color::LightYellow),
+            repo: Style::new().fg(Color::White),
+            repos: HashMap::new(),
+            syntax: Syntax {
+                comment: Style::new().fg(Color::Gray),
+                string: Style::new().fg(Color::LightGreen),
+                variable: Style::new().fg(Color::LightCyan),
+                keyword: bold(Color::LightMagenta),
+                assign: Style::new().fg(Color::LightYellow),
+                function: bold(Color::LightBlue),
+            },
+            ..Self::dark()
+        }
+    }
+
+    pub fn built_in(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::HighContrast => Self::high_contrast(),
+        }
+    }
+
+    // the base theme with the configured elements on top, without any colors
+    // when `no_color` is set (https://no-color.org)
+    pub fn new(config: &ThemeConfig, no_color: bool) -> Result<Self, String> {
+        let mut theme = Self::built_in(config.base);
+        let mut names: Vec<&String> = config.styles.keys().collect();
+        names.sort();
+        for name in names {
+            let style = parse_style(&config.styles[name])
+                .map_err(|e| format!("[theme] {}: {}", name, e))?;
+            if let Some(repo) = name.strip_prefix("repo.") {
+                let base = theme.repo_style(repo);
+                theme.repos.insert(repo.to_string(), base.patch(style));
+                continue;
+            }
+            let element = theme
+                .elements()
+                .into_iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, style)| style)
+                .ok_or_else(|| format!("[theme] unknown element {}", name))?;
+            *element = element.patch(style);
+        }
+        if no_color {
+            theme.strip_colors();
+        }
+        Ok(theme)
+    }
+
+    pub fn repo_style(&self, repo: &str) -> Style {
+        self.repos.get(repo).copied().unwrap_or(self.repo)
+    }
+
+    pub fn notice(&self, level: NoticeLevel) -> Style {
+        match level {
+            NoticeLevel::Info => self.notice_info,
+            NoticeLevel::Warning => self.notice_warning,
+            NoticeLevel::Error => self.notice_error,
+        }
+    }
+
+    // every element by its name in the config
+    fn elements(&mut self) -> Vec<(&'static str, &mut Style)> {
+        vec![
+            ("text", &mut self.text),
+            ("dim", &mut self.dim),
+            ("label", &mut self.label),
+            ("border", &mut self.border),
+            ("selected", &mut self.selected),
+            ("matched", &mut self.matched),
+            ("marked", &mut self.marked),
+            ("installed", &mut self.installed),
+            ("shadowed", &mut self.shadowed),
+            ("text_match", &mut self.text_match),
+            ("chip", &mut self.chip),
+            ("section_header", &mut self.section_header),
+            ("search", &mut self.search),
+            ("search_insert", &mut self.search_insert),
+            ("search_error", &mut self.search_error),
+            ("notice_info", &mut self.notice_info),
+            ("notice_warning", &mut self.notice_warning),
+            ("notice_error", &mut self.notice_error),
+            ("added", &mut self.added),
+            ("removed", &mut self.removed),
+            ("flagged", &mut self.flagged),
+            ("repo", &mut self.repo),
+            ("syntax_comment", &mut self.syntax.comment),
+            ("syntax_string", &mut self.syntax.string),
+            ("syntax_variable", &mut self.syntax.variable),
+            ("syntax_keyword", &mut self.syntax.keyword),
+            ("syntax_assign", &mut self.syntax.assign),
+            ("syntax_function", &mut self.syntax.function),
+        ]
+    }
+
+    // what stays are modifiers, the selection and matches get some of their own
+    fn strip_colors(&mut self) {
+        for (_, style) in self.elements() {
+            style.fg = None;
+            style.bg = None;
+            style.underline_color = None;
+        }
+        for style in self.repos.values_mut() {
+            *style = Style::new();
+        }
+        self.selected = self.selected.add_modifier(Modifier::REVERSED);
+        self.matched = self.matched.add_modifier(Modifier::UNDERLINED);
+        self.chip = self.chip.add_modifier(Modifier::REVERSED);
+    }
+}
+
+// a color name ratatui knows (`red`, `light-blue`, `dark-gray`), `#rrggbb`
+// or an ansi index
+fn parse_color(text: &str) -> Result<Color, String> {
+    Color::from_str(text).map_err(|_| format!("unknown color {}", text))
+}
+
+fn parse_style(config: &StyleConfig) -> Result<Style, String> {
+    let mut style = Style::new();
+    if let Some(fg) = &config.fg {
+        style = style.fg(parse_color(fg)?);
+    }
+    if let Some(bg) = &config.bg {
+        style = style.bg(parse_color(bg)?);
+    }
+    let modifiers = [
+        (config.bold, Modifier::BOLD),
+        (config.italic, Modifier::ITALIC),
+        (config.underline, Modifier::UNDERLINED),
+        (config.dim, Modifier::DIM),
+        (config.reversed, Modifier::REVERSED),
+    ];
+    for (set, modifier) in modifiers {
+        style = match set {
+            Some(true) => style.add_modifier(modifier),
+            Some(false) => style.remove_modifier(modifier),
+            None => style,
+        };
+    }
+    Ok(style)
+}
@@ -2,21 +2,74 @@ use std::{error::Error, time::Duration};
 
 use tokio::{sync::mpsc, time::sleep};
 
-use crate::{backend::aur::get_aur_packages, objects::stat::Package};
+use crate::{
+    backend::aur::{AurClient, get_aur_packages},
+    objects::{
+        config::Config,
+        stat::{App, ItemRepo, Package},
+    },
+    test::mock_aur::{MockAur, MockResponse},
+};
+
+mod aur;
+mod bench;
+mod comments;
+mod details;
+mod dialog;
+mod edit;
+mod filters;
+mod fulltext;
+mod help;
+mod history;
+mod keymap;
+mod layout;
+mod matcher;
+mod mock_aur;
+mod mouse;
+mod navigation;
+mod pkgbuild;
+mod query;
+mod search;
+mod suggest;
+mod table;
+mod theme;
+mod updates;
+
+pub fn package(name: &str, repo: ItemRepo) -> Package {
+    Package::new(
+        false,
+        repo,
+        0,
+        format!("{} description", name),
+        name.to_string(),
+        "1.0-1".to_string(),
+    )
+}
+
+// an app over the given packages whose aur requests can never succeed
+pub fn test_app(pkgs: Vec<Package>) -> App {
+    let config = Config {
+        aur_url: "http://127.0.0.1:9".to_string(),
+        ..Config::default()
+    };
+    App::with_packages(pkgs, config)
+}
 
 #[tokio::test]
 pub async fn test_aur() -> Result<(), Box<dyn Error>> {
-    let (mut tx, mut rx) = mpsc::unbounded_channel::<Vec<Package>>();
+    let mock =
+        MockAur::start(|_| MockResponse::ok(include_str!("test/fixtures/aur_search_vim.json")));
+    let client = AurClient::new(&mock.url, Duration::from_secs(2));
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<Package>>();
 
-    let task = tokio::spawn(async move {
-        let aur_pkgs = get_aur_packages("vim".to_string())
-            .await
-            .unwrap_or_default();
+    tokio::spawn(async move {
+        let aur_pkgs = get_aur_packages(&client, "vim").await.unwrap_or_default();
         let _ = tx.send(aur_pkgs);
     });
 
     loop {
         if let Ok(pkgs) = rx.try_recv() {
+            assert_eq!(pkgs.len(), 3);
             for p in pkgs {
                 println!("{}: {}", p.name, p.descipt);
             }
@@ -0,0 +1,186 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::aur::{AurClient, MAX_INFO_URL_LEN, get_aur_packages, info_paths},
+    objects::stat::ItemRepo,
+    test::mock_aur::{MockAur, MockResponse},
+};
+
+const SEARCH_VIM: &str = include_str!("fixtures/aur_search_vim.json");
+const INFO_PARU: &str = include_str!("fixtures/aur_info_paru.json");
+const ERROR: &str = include_str!("fixtures/aur_error.json");
+const EMPTY: &str = include_str!("fixtures/aur_empty.json");
+
+fn client(mock: &MockAur) -> AurClient {
+    AurClient::new(&mock.url, Duration::from_secs(2))
+}
+
+#[tokio::test]
+async fn search_parses_results() {
+    let mock = MockAur::start(|_| MockResponse::ok(SEARCH_VIM));
+    let results = client(&mock).search("vim").await.unwrap();
+
+    assert_eq!(mock.requests(), ["/rpc/v5/search/vim"]);
+    let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["vim-git", "vim-rust", "vim-nodesc"]);
+    assert_eq!(results[0].num_votes, Some(41));
+    assert_eq!(results[1].out_of_date, Some(1700000000));
+    assert_eq!(results[2].description, None);
+    assert_eq!(results[0].popularity, Some(0.018231));
+    assert_eq!(results[0].last_modified, Some(1726334716));
+}
+
+#[tokio::test]
+async fn search_encodes_the_keyword() {
+    let mock = MockAur::start(|_| MockResponse::ok(EMPTY));
+    client(&mock).search("c++ lib").await.unwrap();
+    assert_eq!(mock.requests(), ["/rpc/v5/search/c%2B%2B%20lib"]);
+}
+
+#[tokio::test]
+async fn aur_packages_are_converted() {
+    let mock = MockAur::start(|_| MockResponse::ok(SEARCH_VIM));
+    let pkgs = get_aur_packages(&client(&mock), "vim").await.unwrap();
+
+    assert_eq!(pkgs.len(), 3);
+    assert_eq!(pkgs[0].name, "vim-git");
+    assert_eq!(pkgs[0].version, "9.1.0727-1");
+    assert!(matches!(pkgs[0].repo, ItemRepo::AUR(41)));
+    assert_eq!(pkgs[2].descipt, "");
+}
+
+#[tokio::test]
+async fn empty_keyword_skips_the_request() {
+    let mock = MockAur::start(|_| MockResponse::ok(SEARCH_VIM));
+    let pkgs = get_aur_packages(&client(&mock), "").await.unwrap();
+    assert!(pkgs.is_empty());
+    assert!(mock.requests().is_empty());
+}
+
+#[tokio::test]
+async fn empty_results() {
+    let mock = MockAur::start(|_| MockResponse::ok(EMPTY));
+    let pkgs = get_aur_packages(&client(&mock), "nothing-matches")
+        .await
+        .unwrap();
+    assert!(pkgs.is_empty());
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn info_returns_details() {
+    let mock = MockAur::start(|_| MockResponse::ok(INFO_PARU));
+    let results = client(&mock).info(&["paru".to_string()]).await.unwrap();
+
+    assert_eq!(mock.requests(), ["/rpc/v5/info?arg[]=paru"]);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].version, "2.0.4-1");
+}
+
+#[tokio::test]
+async fn info_is_split_into_batches() {
+    let mock = MockAur::start(|_| MockResponse::ok(INFO_PARU));
+    let names: Vec<String> = (0..500).map(|i| format!("some-package-{:04}", i)).collect();
+    let results = client(&mock).info(&names).await.unwrap();
+
+    let requests = mock.requests();
+    assert!(requests.len() > 1);
+    assert_eq!(results.len(), requests.len());
+    for path in &requests {
+        assert!(mock.url.len() + path.len() <= MAX_INFO_URL_LEN);
+    }
+    let args: usize = requests.iter().map(|p| p.matches("arg[]=").count()).sum();
+    assert_eq!(args, names.len());
+}
+
+#[test]
+fn info_paths_are_encoded_and_batched() {
+    let names = ["c++".to_string(), "paru".to_string()];
+    assert_eq!(
+        info_paths("https://aur.archlinux.org", &names),
+        ["/rpc/v5/info?arg[]=c%2B%2B&arg[]=paru"]
+    );
+    assert!(info_paths("https://aur.archlinux.org", &[]).is_empty());
+
+    // the base url counts towards the limit
+    let base_url = "x".repeat(MAX_INFO_URL_LEN - 100);
+    let names: Vec<String> = (0..10).map(|i| format!("package-{}", i)).collect();
+    let paths = info_paths(&base_url, &names);
+    assert_eq!(paths.len(), 2);
+    assert!(
+        paths
+            .iter()
+            .all(|p| base_url.len() + p.len() <= MAX_INFO_URL_LEN)
+    );
+    assert!(paths[1].starts_with("/rpc/v5/info?arg[]=package-"));
+}
+
+#[tokio::test]
+async fn info_without_names_skips_the_request() {
+    let mock = MockAur::start(|_| MockResponse::ok(INFO_PARU));
+    assert!(client(&mock).info(&[]).await.unwrap().is_empty());
+    assert!(mock.requests().is_empty());
+}
+
+#[tokio::test]
+async fn error_payload() {
+    let mock = MockAur::start(|_| MockResponse::ok(ERROR));
+    let err = client(&mock).search("a").await.unwrap_err();
+    assert_eq!(err.to_string(), "aur rpc error: Too many package results.");
+}
+
+#[tokio::test]
+async fn error_payload_with_error_status() {
+    let mock = MockAur::start(|_| MockResponse::ok(ERROR).status(400));
+    let err = client(&mock).search("a").await.unwrap_err();
+    assert_eq!(err.to_string(), "aur rpc error: Too many package results.");
+}
+
+#[tokio::test]
+async fn http_error_status() {
+    let mock = MockAur::start(|_| MockResponse::ok("<html>bad gateway</html>").status(502));
+    let err = client(&mock).search("vim").await.unwrap_err();
+    assert!(err.to_string().contains("502"), "{}", err);
+}
+
+#[tokio::test]
+async fn malformed_json() {
+    let mock = MockAur::start(|_| MockResponse::ok(r#"{"resultcount": 1, "results": [{"Name": "#));
+    let err = client(&mock).search("vim").await.unwrap_err();
+    assert!(
+        err.to_string().starts_with("malformed aur rpc response"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn unexpected_json_shape() {
+    let mock = MockAur::start(|_| MockResponse::ok(r#"{"results": [{"Name": 3}]}"#));
+    assert!(client(&mock).search("vim").await.is_err());
+}
+
+#[tokio::test]
+async fn timeout() {
+    let mock = MockAur::start(|_| MockResponse::ok(SEARCH_VIM).delay(Duration::from_secs(2)));
+    let client = AurClient::new(&mock.url, Duration::from_millis(200));
+
+    let started = Instant::now();
+    let err = get_aur_packages(&client, "vim").await.unwrap_err();
+    assert!(err.is::<tokio::time::error::Elapsed>(), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn plain_text_endpoints_use_the_base_url() {
+    let mock = MockAur::start(|_| MockResponse::ok("pkgname=paru"));
+    let client = AurClient::new(&format!("{}/", mock.url), Duration::from_secs(2));
+
+    let body = client
+        .get_text("/cgit/aur.git/plain/PKGBUILD?h=paru")
+        .await
+        .unwrap();
+    assert_eq!(body, "pkgname=paru");
+    assert_eq!(mock.requests(), ["/cgit/aur.git/plain/PKGBUILD?h=paru"]);
+    assert_eq!(client.git_url("paru"), format!("{}/paru.git", mock.url));
+}
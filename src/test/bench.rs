@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    objects::{
+        matcher::FuzzyMatch,
+        query::Query,
+        stat::{ItemRepo, Package},
+    },
+    test::{package, test_app},
+};
+
+const WORDS: [&str; 12] = [
+    "python", "lib", "qt", "gtk", "rust", "perl", "font", "git", "kde", "vim", "theme", "plugin",
+];
+
+// about the size of core, extra and multilib together
+fn packages() -> Vec<Package> {
+    (0..16_000)
+        .map(|i| {
+            let name = format!(
+                "{}-{}-{}",
+                WORDS[i % WORDS.len()],
+                WORDS[(i / WORDS.len()) % WORDS.len()],
+                i
+            );
+            package(&name, ItemRepo::Extra)
+        })
+        .collect()
+}
+
+// what every keystroke did before the search index: rank and clone every
+// matching package
+fn clone_filter(items: &[Package], search: &str) -> Vec<Package> {
+    let query = Query::parse(search).unwrap_or_default();
+    let mut hits: Vec<(FuzzyMatch, &Package)> = items
+        .iter()
+        .filter_map(|p| query.rank(p, true).map(|m| (m, p)))
+        .collect();
+    hits.sort_by(|a, b| FuzzyMatch::rank((&a.0, &a.1.name), (&b.0, &b.1.name)));
+    hits.into_iter().map(|(_, p)| p.clone()).collect()
+}
+
+fn typing(mut keystroke: impl FnMut(&str)) -> Duration {
+    let query = "python-gtk";
+    let start = Instant::now();
+    for end in 1..=query.len() {
+        keystroke(&query[..end]);
+    }
+    start.elapsed()
+}
+
+// cargo test --release bench -- --ignored --nocapture
+#[tokio::test]
+#[ignore]
+async fn bench_typing() {
+    let items = packages();
+    let mut expected = Vec::new();
+    let baseline = typing(|search| {
+        expected = std::hint::black_box(clone_filter(&items, search));
+    });
+
+    let mut app = test_app(items);
+    let indexed = typing(|search| {
+        app.search = search.to_string();
+        app.update_filter_local();
+    });
+
+    // timings vary too much between machines to assert on, only the
+    // results have to agree
+    println!("cloning: {:?}, indexed: {:?}", baseline, indexed);
+    let names: Vec<&str> = app.filtered_packages().map(|p| p.name.as_str()).collect();
+    let expected: Vec<&str> = expected.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, expected);
+}
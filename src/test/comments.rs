@@ -0,0 +1,54 @@
+use crate::backend::comments::parse_comments;
+
+const PAGE: &str = include_str!("fixtures/aur_comments.html");
+const EMPTY_PAGE: &str = include_str!("fixtures/aur_comments_empty.html");
+
+#[test]
+fn parses_pinned_and_latest_comments() {
+    let comments = parse_comments(PAGE);
+    let ids: Vec<u64> = comments.iter().map(|c| c.id).collect();
+    assert_eq!(ids, [912345, 998877, 998001]);
+    assert!(comments[0].pinned);
+    assert!(!comments[1].pinned && !comments[2].pinned);
+}
+
+#[test]
+fn parses_comment_headers() {
+    let comments = parse_comments(PAGE);
+
+    assert_eq!(comments[0].author, "Morganamilo");
+    assert_eq!(comments[0].date, "2024-06-01 10:02 (UTC)");
+    assert_eq!(
+        comments[0].edited.as_deref(),
+        Some("edited on 2024-06-02 08:00 (UTC) by Morganamilo")
+    );
+
+    // deleted accounts are rendered without a link
+    assert_eq!(comments[2].author, "bob");
+    assert_eq!(comments[2].date, "2024-09-10 07:03 (UTC)");
+    assert_eq!(comments[2].edited, None);
+}
+
+#[test]
+fn converts_comment_bodies_to_text() {
+    let comments = parse_comments(PAGE);
+
+    assert_eq!(
+        comments[0].content,
+        "If the build fails with error: linker `cc` not found install base-devel.\n\n\
+         Use the issue tracker for bugs & feature requests.\nThanks!"
+    );
+    assert_eq!(
+        comments[1].content,
+        "Build fails against libalpm 15:\n\n\
+         error[E0308]: mismatched types <alpm::Db>\n  --> src/lib.rs:12:5\n\n\
+         Workaround: paru -S paru-bin \u{2014} or wait for the next release."
+    );
+    assert_eq!(comments[2].content, "- first\n- second \"quoted\" 'single'");
+}
+
+#[test]
+fn page_without_comments() {
+    assert!(parse_comments(EMPTY_PAGE).is_empty());
+    assert!(parse_comments("").is_empty());
+}
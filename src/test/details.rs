@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use ratatui::{Terminal, backend::TestBackend};
+
+use crate::{
+    backend::{
+        aur::AurClient,
+        details::{HistoryEntry, PackageDeps, fetch_dependencies, parse_history},
+    },
+    objects::stat::{InfoTab, ItemRepo, LoadError},
+    test::{
+        mock_aur::{MockAur, MockResponse},
+        package, test_app,
+    },
+    ui::render,
+};
+
+const LOG: &str = "\
+[2023-11-02T09:12:44+0100] [PACMAN] Running 'pacman -S ripgrep'
+[2023-11-02T09:12:45+0100] [ALPM] installed ripgrep (13.0.0-3)
+[2023-11-02T09:12:45+0100] [ALPM] installed ripgrep-all (0.10.6-1)
+[2024-01-15T20:01:02+0100] [ALPM] upgraded ripgrep (13.0.0-3 -> 14.1.0-1)
+[2024-01-15T20:01:02+0100] [ALPM-SCRIPTLET] ripgrep (14.1.0-1) hook
+[2024-02-01T08:00:00+0100] [ALPM] removed ripgrep (14.1.0-1)
+";
+
+#[test]
+fn history_from_the_log() {
+    let entries = parse_history(LOG, "ripgrep");
+    assert_eq!(
+        entries,
+        [
+            HistoryEntry {
+                date: "2023-11-02 09:12".to_string(),
+                action: "installed".to_string(),
+                version: "13.0.0-3".to_string(),
+            },
+            HistoryEntry {
+                date: "2024-01-15 20:01".to_string(),
+                action: "upgraded".to_string(),
+                version: "13.0.0-3 -> 14.1.0-1".to_string(),
+            },
+            HistoryEntry {
+                date: "2024-02-01 08:00".to_string(),
+                action: "removed".to_string(),
+                version: "14.1.0-1".to_string(),
+            },
+        ]
+    );
+    assert!(parse_history(LOG, "rip").is_empty());
+}
+
+#[test]
+fn empty_dependency_lists_are_left_out() {
+    let deps = PackageDeps {
+        name: "paru".to_string(),
+        depends: vec!["git".to_string()],
+        conflicts: vec!["paru-bin".to_string()],
+        ..Default::default()
+    };
+    let titles: Vec<&str> = deps.sections().into_iter().map(|(t, _)| t).collect();
+    assert_eq!(titles, ["depends on", "conflicts with"]);
+}
+
+#[tokio::test]
+async fn aur_dependencies_come_from_the_rpc() {
+    let mock = MockAur::start(|_| MockResponse::ok(include_str!("fixtures/aur_info_paru.json")));
+    let client = AurClient::new(&mock.url, Duration::from_secs(2));
+    let deps = fetch_dependencies(&client, "paru".to_string(), true)
+        .await
+        .unwrap();
+
+    assert_eq!(mock.requests(), ["/rpc/v5/info?arg[]=paru"]);
+    assert_eq!(deps.depends, ["git", "pacman"]);
+    assert_eq!(deps.makedepends, ["cargo"]);
+    assert_eq!(deps.conflicts, ["paru-bin", "paru-git"]);
+    assert!(deps.required_by.is_empty());
+
+    assert!(
+        fetch_dependencies(&client, "yay".to_string(), true)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn tabs_scroll_independently() {
+    let mut app = test_app(vec![package("paru", ItemRepo::AUR(1))]);
+    app.info_tab = InfoTab::Files;
+    app.scroll_info(true);
+    app.scroll_info(true);
+    app.info_tab = InfoTab::History;
+    app.scroll_info(true);
+    app.scroll_info(false);
+    app.scroll_info(false);
+
+    assert_eq!(app.info_scroll(InfoTab::Files), 2);
+    assert_eq!(app.info_scroll(InfoTab::History), 0);
+    assert_eq!(app.info_scroll(InfoTab::Info), 0);
+    assert_eq!(InfoTab::Updates.next(), InfoTab::Info);
+    assert_eq!(InfoTab::Info.next(), InfoTab::Dependencies);
+}
+
+#[tokio::test]
+async fn tabs_load_lazily() {
+    let mut app = test_app(vec![package("paru", ItemRepo::AUR(1))]);
+    app.load_info_tab();
+    assert!(app.deps_task.is_none() && app.files_task.is_none());
+
+    app.info_tab = InfoTab::Dependencies;
+    app.load_info_tab();
+    assert!(app.deps_task.is_some());
+    assert!(app.files_task.is_none() && app.history_task.is_none());
+
+    // nothing is fetched again once the tab has the selection's data
+    app.package_deps = Some(PackageDeps {
+        name: "paru".to_string(),
+        ..Default::default()
+    });
+    app.deps_task = None;
+    app.load_info_tab();
+    assert!(app.deps_task.is_none());
+}
+
+#[tokio::test]
+async fn loads_for_another_package_are_dropped() {
+    let mut app = test_app(vec![
+        package("ripgrep", ItemRepo::Extra),
+        package("fd", ItemRepo::Extra),
+    ]);
+    app.list_state.select(Some(1));
+    let selected = app.selected_package().unwrap().name.clone();
+    let other = if selected == "fd" { "ripgrep" } else { "fd" };
+    let deps = |name: &str| PackageDeps {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    // the user moved on before these arrived
+    let late = app.take_loaded(InfoTab::Dependencies, Ok(deps(other)), |d| &d.name);
+    assert!(late.is_none());
+    let failed = Err::<PackageDeps, _>(LoadError::new(other, "no such file"));
+    assert!(
+        app.take_loaded(InfoTab::Dependencies, failed, |d| &d.name)
+            .is_none()
+    );
+    assert!(app.load_errors.is_empty());
+    assert!(app.notice.text.is_empty());
+
+    let current = app.take_loaded(InfoTab::Dependencies, Ok(deps(&selected)), |d| &d.name);
+    assert_eq!(current.map(|d| d.name), Some(selected.clone()));
+    let failed = Err::<PackageDeps, _>(LoadError::new(&selected, "no such file"));
+    app.take_loaded(InfoTab::Dependencies, failed, |d| &d.name);
+    assert_eq!(
+        app.load_error(InfoTab::Dependencies, &selected),
+        Some("no such file")
+    );
+}
+
+#[tokio::test]
+async fn failed_loads_are_not_retried() {
+    let mut app = test_app(vec![package("ripgrep", ItemRepo::Extra)]);
+    app.list_state.select(Some(0));
+    for tab in [InfoTab::Dependencies, InfoTab::Files, InfoTab::History] {
+        app.info_tab = tab;
+        app.load_failed(tab, LoadError::new("ripgrep", "no such file"));
+        app.load_info_tab();
+    }
+    assert!(app.deps_task.is_none() && app.files_task.is_none() && app.history_task.is_none());
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("failed to load ripgrep: no such file"));
+}
@@ -0,0 +1,235 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{Terminal, backend::TestBackend};
+
+use crate::{
+    objects::{
+        dialog::{Answer, Dialog, DialogEvent, DialogKind, Purpose},
+        stat::{App, ItemRepo, NoticeLevel},
+    },
+    test::{package, test_app},
+    ui::render,
+};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(key(code));
+}
+
+fn app() -> App {
+    let mut installed = package("vim", ItemRepo::Extra);
+    installed.is_installed = true;
+    let mut app = test_app(vec![
+        installed,
+        package("emacs", ItemRepo::Extra),
+        package("nano", ItemRepo::Core),
+    ]);
+    app.list_state.select(Some(0));
+    app
+}
+
+#[test]
+fn confirm() {
+    let mut dialog = Dialog::confirm("Remove", "sure?".to_string(), Purpose::Batch);
+    // no is focused, Enter on it is a no
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Enter)),
+        DialogEvent::Cancelled
+    );
+    assert_eq!(dialog.handle_key(key(KeyCode::Tab)), DialogEvent::Open);
+    assert_eq!(dialog.kind, DialogKind::Confirm { yes: true });
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Enter)),
+        DialogEvent::Answered(Answer::Yes)
+    );
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Char('y'))),
+        DialogEvent::Answered(Answer::Yes)
+    );
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Char('n'))),
+        DialogEvent::Cancelled
+    );
+    assert_eq!(dialog.handle_key(key(KeyCode::Esc)), DialogEvent::Cancelled);
+}
+
+#[test]
+fn input() {
+    let mut dialog = Dialog::input("Keep", "how many".to_string(), "3", Purpose::KeepVersions);
+    dialog.handle_key(key(KeyCode::Char('0')));
+    dialog.handle_key(key(KeyCode::Home));
+    dialog.handle_key(key(KeyCode::Char('1')));
+    dialog.handle_key(key(KeyCode::Right));
+    dialog.handle_key(key(KeyCode::Backspace));
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Enter)),
+        DialogEvent::Answered(Answer::Text("10".to_string()))
+    );
+    // q is text here, only Esc cancels
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Char('q'))),
+        DialogEvent::Open
+    );
+    assert_eq!(dialog.handle_key(key(KeyCode::Esc)), DialogEvent::Cancelled);
+}
+
+#[test]
+fn pick() {
+    let options = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let mut dialog = Dialog::pick("Pick", String::new(), options, Purpose::Batch);
+    dialog.handle_key(key(KeyCode::Char('j')));
+    dialog.handle_key(key(KeyCode::Char('j')));
+    dialog.handle_key(key(KeyCode::Char('j')));
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Enter)),
+        DialogEvent::Answered(Answer::Picked(2))
+    );
+    dialog.handle_key(key(KeyCode::Up));
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Enter)),
+        DialogEvent::Answered(Answer::Picked(1))
+    );
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Char('1'))),
+        DialogEvent::Answered(Answer::Picked(0))
+    );
+    assert_eq!(
+        dialog.handle_key(key(KeyCode::Char('4'))),
+        DialogEvent::Open
+    );
+}
+
+#[tokio::test]
+async fn removal_asks_first() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('l'));
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(dialog.purpose, Purpose::RemovePackage("vim".to_string()));
+    assert!(dialog.message.contains("vim"));
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content
+        .iter()
+        .map(|c| c.symbol())
+        .collect();
+    assert!(screen.contains("[ Yes ]  [ No ]"));
+
+    // the dialog takes the keys until it is answered
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.list_state.selected(), Some(0));
+    press(&mut app, KeyCode::Char('n'));
+    assert!(app.dialog.is_none());
+    assert!(app.items[0].is_installed);
+    assert!(!app.exit);
+}
+
+#[tokio::test]
+async fn batch() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('b'));
+    assert!(app.dialog.is_none());
+    assert_eq!(app.notice.level, NoticeLevel::Warning);
+
+    app.marked.insert("emacs".to_string());
+    app.marked.insert("nano".to_string());
+    assert_eq!(app.marked_packages(false), ["emacs", "nano"]);
+    assert!(app.marked_packages(true).is_empty());
+    press(&mut app, KeyCode::Char('b'));
+    let Some(DialogKind::Pick { options, .. }) = app.dialog.as_ref().map(|d| &d.kind) else {
+        panic!("no pick dialog");
+    };
+    assert_eq!(options[0], "install 2 not installed");
+    assert_eq!(options[1], "remove 0 installed");
+
+    // nothing to remove, so no confirmation either
+    press(&mut app, KeyCode::Char('2'));
+    assert!(app.dialog.is_none());
+    assert_eq!(app.notice.level, NoticeLevel::Warning);
+
+    app.marked.insert("vim".to_string());
+    press(&mut app, KeyCode::Char('b'));
+    press(&mut app, KeyCode::Char('2'));
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(dialog.purpose, Purpose::RemoveMarked);
+    assert!(dialog.message.contains("vim"));
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Char('b'));
+    press(&mut app, KeyCode::Char('3'));
+    assert!(app.marked.is_empty());
+
+    // nothing to install, paru is not run and the terminal stays as it is
+    app.marked.insert("vim".to_string());
+    press(&mut app, KeyCode::Char('b'));
+    press(&mut app, KeyCode::Char('1'));
+    assert!(app.dialog.is_none());
+    assert_eq!(app.notice.text, "all marked packages are installed");
+    assert_eq!(app.marked.len(), 1);
+}
+
+#[tokio::test]
+async fn cache_cleanup() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('C'));
+    assert_eq!(app.dialog.as_ref().unwrap().purpose, Purpose::CleanCache);
+    press(&mut app, KeyCode::Enter);
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(dialog.purpose, Purpose::KeepVersions);
+    assert_eq!(
+        dialog.kind,
+        DialogKind::Input {
+            text: "3".to_string(),
+            cursor: 1
+        }
+    );
+
+    // asked again until the answer is a number
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Enter);
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(dialog.error.as_deref(), Some("3x is not a number"));
+
+    // paccache would remove every cached version without asking
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Char('0'));
+    press(&mut app, KeyCode::Enter);
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(dialog.purpose, Purpose::KeepVersions);
+    assert_eq!(dialog.error.as_deref(), Some("keep at least 1 version"));
+    press(&mut app, KeyCode::Esc);
+    assert!(app.dialog.is_none());
+}
+
+#[tokio::test]
+async fn pastes_go_to_the_dialog() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('C'));
+    // a pick dialog has no use for it
+    app.paste("vim");
+    assert_eq!(app.search, "");
+    assert!(!app.insert_mode.enabled);
+
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Home);
+    app.paste("1");
+    let dialog = app.dialog.as_ref().unwrap();
+    assert_eq!(
+        dialog.kind,
+        DialogKind::Input {
+            text: "13".to_string(),
+            cursor: 1
+        }
+    );
+    press(&mut app, KeyCode::Esc);
+
+    press(&mut app, KeyCode::Char('?'));
+    app.paste("vim");
+    assert_eq!(app.search, "");
+    assert!(app.help.is_some());
+}
@@ -0,0 +1,176 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    objects::{
+        edit::{rubout_start, word_left, word_right},
+        history::History,
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+};
+
+fn typed(search: &str) -> App {
+    let mut app = test_app(vec![package("neovim", ItemRepo::Extra)]);
+    app.insert_mode.enabled = true;
+    for c in search.chars() {
+        key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    app
+}
+
+fn key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    app.handle_key(KeyEvent::new(code, modifiers));
+}
+
+fn ctrl(app: &mut App, c: char) {
+    key(app, KeyCode::Char(c), KeyModifiers::CONTROL);
+}
+
+fn alt(app: &mut App, c: char) {
+    key(app, KeyCode::Char(c), KeyModifiers::ALT);
+}
+
+#[test]
+fn word_boundaries() {
+    let text = "vim -git repo:extra";
+    assert_eq!(word_left(text, 19), 14);
+    assert_eq!(word_left(text, 14), 9);
+    assert_eq!(word_left(text, 9), 5);
+    assert_eq!(word_left(text, 0), 0);
+    assert_eq!(word_right(text, 0), 3);
+    assert_eq!(word_right(text, 3), 8);
+    assert_eq!(word_right(text, 19), 19);
+    assert_eq!(rubout_start(text, 19), 9);
+    assert_eq!(rubout_start(text, 8), 4);
+    assert_eq!(rubout_start("vim  ", 5), 0);
+}
+
+#[tokio::test]
+async fn motions() {
+    let mut app = typed("vim -git repo:extra");
+    ctrl(&mut app, 'a');
+    assert_eq!(app.insert_mode.index, 0);
+    alt(&mut app, 'f');
+    alt(&mut app, 'f');
+    assert_eq!(app.insert_mode.index, 8);
+    alt(&mut app, 'b');
+    assert_eq!(app.insert_mode.index, 5);
+    key(&mut app, KeyCode::End, KeyModifiers::NONE);
+    assert_eq!(app.insert_mode.index, 19);
+    key(&mut app, KeyCode::Home, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+    assert_eq!(app.search, "im -git repo:extra");
+    ctrl(&mut app, 'e');
+    assert_eq!(app.insert_mode.index, 18);
+}
+
+#[tokio::test]
+async fn kills_and_yank() {
+    let mut app = typed("vim -git repo:extra");
+    ctrl(&mut app, 'w');
+    assert_eq!(app.search, "vim -git ");
+    ctrl(&mut app, 'a');
+    ctrl(&mut app, 'y');
+    assert_eq!(app.search, "repo:extravim -git ");
+    alt(&mut app, 'b');
+    ctrl(&mut app, 'k');
+    assert_eq!(app.search, "repo:");
+    assert_eq!(app.edits.killed, "extravim -git ");
+    ctrl(&mut app, 'u');
+    assert_eq!((app.search.as_str(), app.insert_mode.index), ("", 0));
+    assert_eq!(app.edits.killed, "repo:");
+}
+
+#[tokio::test]
+async fn undo() {
+    let mut app = typed("vim");
+    key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+    ctrl(&mut app, 'w');
+    assert_eq!(app.search, "");
+    for c in "emacs".chars() {
+        key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    key(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+    assert_eq!(app.search, "ema");
+
+    // the backspaces go together, then the typed word, then the kill
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "emacs");
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "");
+    ctrl(&mut app, 'z');
+    assert_eq!((app.search.as_str(), app.insert_mode.index), ("vim ", 4));
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "");
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "");
+
+    // a cursor motion starts a new step
+    let mut app = typed("ab");
+    key(&mut app, KeyCode::Left, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+    assert_eq!(app.search, "axb");
+    ctrl(&mut app, 'z');
+    assert_eq!((app.search.as_str(), app.insert_mode.index), ("ab", 1));
+
+    // ctrl-_ arrives as ctrl-7
+    key(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+    ctrl(&mut app, '7');
+    assert_eq!(app.search, "ab");
+}
+
+#[tokio::test]
+async fn paste() {
+    let mut app = typed("vim ");
+    app.insert_mode.enabled = false;
+    app.paste("repo:extra\n-git\n");
+    assert!(app.insert_mode.enabled);
+    assert_eq!(app.search, "vim repo:extra -git");
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "vim ");
+}
+
+#[tokio::test]
+async fn recalled_searches_are_undone() {
+    let mut app = typed("vi");
+    app.history = History::new(vec!["neovim".to_string(), "emacs".to_string()], 10, None);
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(app.search, "neovim");
+    // walking the history is one step
+    ctrl(&mut app, 'z');
+    assert_eq!((app.search.as_str(), app.insert_mode.index), ("vi", 2));
+
+    ctrl(&mut app, 'r');
+    for c in "ema".chars() {
+        key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.search, "emacs");
+    ctrl(&mut app, 'z');
+    assert_eq!(app.search, "vi");
+}
+
+#[tokio::test]
+async fn paste_into_reverse_search() {
+    let mut app = typed("vi");
+    app.history = History::new(vec!["neovim".to_string(), "emacs".to_string()], 10, None);
+    ctrl(&mut app, 'r');
+    app.paste("neo");
+    assert_eq!(app.history.search.as_ref().unwrap().pattern, "neo");
+    assert_eq!(app.search, "neovim");
+    key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+    assert_eq!(app.search, "vi");
+}
+
+#[tokio::test]
+async fn wide_cursor() {
+    let mut app = typed("日本語");
+    assert_eq!(app.insert_mode.index, 3);
+    assert_eq!(app.cursor_column(), 6);
+    key(&mut app, KeyCode::Left, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+    assert_eq!(app.search, "日本a語");
+    assert_eq!(app.cursor_column(), 5);
+}
@@ -0,0 +1,64 @@
+use crate::{
+    objects::{
+        filters::{FilterOption, Filters, InstalledFilter, SourceFilter},
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+};
+
+fn names(app: &App) -> Vec<&str> {
+    app.filtered_packages().map(|p| p.name.as_str()).collect()
+}
+
+#[test]
+fn exclusive_options() {
+    let mut filters = Filters::default();
+    filters.toggle(&FilterOption::RepoOnly);
+    filters.toggle(&FilterOption::AurOnly);
+    assert_eq!(filters.source, SourceFilter::Aur);
+    filters.toggle(&FilterOption::AurOnly);
+    assert_eq!(filters.source, SourceFilter::All);
+
+    filters.toggle(&FilterOption::Installed);
+    filters.toggle(&FilterOption::NotInstalled);
+    assert_eq!(filters.installed, InstalledFilter::NotInstalled);
+
+    filters.toggle(&FilterOption::Repo("core".to_string()));
+    assert!(!filters.is_checked(&FilterOption::Repo("core".to_string())));
+    assert!(filters.is_checked(&FilterOption::Repo("extra".to_string())));
+    assert_eq!(filters.chips(), ["-core", "not installed"]);
+
+    let saved = toml::to_string(&filters).unwrap();
+    assert_eq!(toml::from_str::<Filters>(&saved).unwrap(), filters);
+}
+
+#[tokio::test]
+async fn filtering_the_results() {
+    let mut vim = package("vim", ItemRepo::Extra);
+    vim.is_installed = true;
+    vim.explicit = true;
+    let mut libsodium = package("libsodium", ItemRepo::Core);
+    libsodium.is_installed = true;
+    libsodium.orphan = true;
+    libsodium.upgradable = true;
+    let mut app = test_app(vec![libsodium, package("pacman", ItemRepo::Core), vim]);
+    assert_eq!(app.repos, ["core", "extra"]);
+
+    let toggle = |app: &mut App, option: FilterOption| {
+        app.filters.toggle(&option);
+        app.merge_results();
+    };
+    toggle(&mut app, FilterOption::Installed);
+    assert_eq!(names(&app), ["libsodium", "vim"]);
+    toggle(&mut app, FilterOption::Orphans);
+    assert_eq!(names(&app), ["libsodium"]);
+    toggle(&mut app, FilterOption::Orphans);
+    toggle(&mut app, FilterOption::Explicit);
+    assert_eq!(names(&app), ["vim"]);
+    toggle(&mut app, FilterOption::Repo("extra".to_string()));
+    assert!(names(&app).is_empty());
+    toggle(&mut app, FilterOption::AurOnly);
+    toggle(&mut app, FilterOption::Explicit);
+    toggle(&mut app, FilterOption::Repo("extra".to_string()));
+    assert!(names(&app).is_empty());
+}
@@ -0,0 +1,132 @@
+use crate::{
+    backend::fulltext::indexed_file,
+    objects::{
+        fulltext::{Doc, Field, FullTextIndex},
+        stat::{ItemRepo, NoticeLevel, Source},
+    },
+    test::{package, test_app},
+};
+
+fn doc(name: &str, repo: &str, description: &str) -> Doc {
+    Doc {
+        name: name.to_string(),
+        repo: repo.to_string(),
+        version: "1.0-1".to_string(),
+        description: description.to_string(),
+        ..Doc::default()
+    }
+}
+
+fn index() -> FullTextIndex {
+    let mut ripgrep = doc(
+        "ripgrep",
+        "extra",
+        "A search tool that combines ag with grep",
+    );
+    ripgrep.files = vec!["usr/bin/rg".to_string()];
+    let mut zathura = doc("zathura-pdf-mupdf", "extra", "PDF support for zathura");
+    zathura.provides = vec!["zathura-pdf".to_string()];
+    let docs = vec![
+        ripgrep,
+        doc("evince", "extra", "Document viewer (PDF, PostScript, XPS)"),
+        doc("okular", "extra", "Document Viewer"),
+        zathura,
+        doc("pdfviewer-bin", "aur", "Yet another viewer"),
+    ];
+    FullTextIndex::build(docs, Vec::new())
+}
+
+fn names(index: &FullTextIndex, query: &str) -> Vec<String> {
+    index
+        .search(query)
+        .into_iter()
+        .map(|hit| index.docs[hit.doc].name.clone())
+        .collect()
+}
+
+#[test]
+fn all_words_rank_first() {
+    let index = index();
+    let found = names(&index, "which package has a pdf viewer");
+    // both match the two words, pdfviewer-bin by its name, and come before
+    // the packages matching only one
+    let mut both = found[..2].to_vec();
+    both.sort();
+    assert_eq!(both, ["evince", "pdfviewer-bin"]);
+    assert!(found[2..].contains(&"okular".to_string()));
+    assert!(index.search("the").is_empty());
+
+    // a repeated word counts once, wherever it is repeated
+    let score = |query: &str| index.search(query)[0].score;
+    assert_eq!(score("pdf viewer pdf"), score("pdf viewer"));
+}
+
+#[test]
+fn matched_fields() {
+    let index = index();
+    let hits = index.search("bin/rg");
+    assert_eq!(index.docs[hits[0].doc].name, "ripgrep");
+    assert_eq!(hits[0].matched.field, Field::File);
+    assert_eq!(hits[0].matched.text, "usr/bin/rg");
+    assert_eq!(hits[0].matched.field.label(), "file");
+
+    let hits = index.search("zathura-pdf");
+    assert_eq!(hits[0].matched.field, Field::Name);
+    let hits = index.search("postscript");
+    assert_eq!(hits[0].matched.field.label(), "desc");
+}
+
+#[test]
+fn indexed_files() {
+    assert!(indexed_file("usr/bin/rg"));
+    assert!(indexed_file("usr/lib/libz.so.1"));
+    assert!(indexed_file(
+        "usr/share/applications/org.gnome.Evince.desktop"
+    ));
+    assert!(indexed_file("usr/lib/systemd/system/sshd.service"));
+    assert!(!indexed_file("usr/bin/"));
+    assert!(!indexed_file("usr/share/doc/ripgrep/README.md"));
+    assert!(!indexed_file("usr/lib/python3.12/site-packages/foo.so"));
+}
+
+#[tokio::test]
+async fn fulltext_mode() {
+    let mut app = test_app(vec![
+        package("ripgrep", ItemRepo::Extra),
+        package("evince", ItemRepo::Extra),
+    ]);
+    app.fulltext = Some(index());
+    app.toggle_fulltext_mode();
+    app.search = "pdf viewer".to_string();
+    app.update_filter_local();
+
+    let found: Vec<&str> = app.filtered_packages().map(|p| p.name.as_str()).collect();
+    assert_eq!(found[..2], ["pdfviewer-bin", "evince"]);
+    // repo packages are the ones of the list, aur ones come from the index
+    assert!(matches!(app.filtered[0].source, Source::Text(_)));
+    assert!(matches!(
+        app.package(app.filtered[0].source).repo,
+        ItemRepo::AUR(_)
+    ));
+    assert_eq!(app.filtered[1].source, Source::Repo(1));
+    assert!(app.filtered[1].matched.is_some());
+    assert!(app.aur_task.is_none());
+
+    app.toggle_fulltext_mode();
+    app.search = "rip".to_string();
+    app.update_filter_local();
+    assert!(app.filtered.iter().all(|hit| hit.matched.is_none()));
+}
+
+#[tokio::test]
+async fn failed_metadata_refresh_is_reported() {
+    let mut app = test_app(vec![package("evince", ItemRepo::Extra)]);
+    app.apply_fulltext_index(index(), Some("connection refused".to_string()));
+    assert_eq!(app.notice.level, NoticeLevel::Error);
+    assert_eq!(
+        app.notice.text,
+        "5 packages indexed, 1 of them from the aur, refreshing the aur metadata failed: \
+         connection refused"
+    );
+    assert!(app.fulltext.is_some());
+}
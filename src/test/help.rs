@@ -0,0 +1,113 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    objects::{
+        config::Config,
+        help::{Help, HelpLine},
+        keymap::{Action, Category, KeyMode, Keymap},
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+};
+
+fn bindings(lines: &[HelpLine]) -> Vec<(&str, Action)> {
+    lines
+        .iter()
+        .filter_map(|line| match line {
+            HelpLine::Binding { keys, action } => Some((keys.as_str(), *action)),
+            HelpLine::Category(_) => None,
+        })
+        .collect()
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+#[test]
+fn grouped_by_category() {
+    let lines = Help::new(KeyMode::Normal).lines(&Keymap::default());
+    assert_eq!(lines[0], HelpLine::Category(Category::Movement));
+    let categories: Vec<Category> = lines
+        .iter()
+        .filter_map(|line| match line {
+            HelpLine::Category(category) => Some(*category),
+            HelpLine::Binding { .. } => None,
+        })
+        .collect();
+    let mut sorted = categories.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(categories, sorted);
+    assert!(bindings(&lines).contains(&("j, <Down>", Action::SelectNext)));
+    assert!(bindings(&lines).contains(&("gg, <Home>", Action::SelectFirst)));
+}
+
+#[test]
+fn follows_the_keymap() {
+    let config =
+        toml::from_str::<Config>("[keys.normal]\n\"<C-n>\" = \"select-next\"\n\"q\" = \"unbound\"")
+            .unwrap();
+    let keymap = Keymap::new(&config.keys).unwrap();
+    let lines = Help::new(KeyMode::Normal).lines(&keymap);
+    assert!(bindings(&lines).contains(&("j, <Down>, <C-n>", Action::SelectNext)));
+    assert!(bindings(&lines).contains(&("<Esc>", Action::Quit)));
+
+    let insert = Help::new(KeyMode::Insert).lines(&keymap);
+    assert!(bindings(&insert).contains(&("<C-r>", Action::HistorySearch)));
+    assert!(!bindings(&insert).iter().any(|(_, a)| *a == Action::Install));
+}
+
+#[test]
+fn search() {
+    let keymap = Keymap::default();
+    let mut help = Help::new(KeyMode::Normal);
+    help.query = "tab".to_string();
+    let lines = help.lines(&keymap);
+    assert_eq!(lines[0], HelpLine::Category(Category::Search));
+    assert!(bindings(&lines).contains(&("]", Action::NextTab)));
+    assert!(!bindings(&lines).iter().any(|(_, a)| *a == Action::Quit));
+
+    help.query = "<A-".to_string();
+    assert!(help.lines(&keymap).is_empty());
+    help.query = "upgrade-aur".to_string();
+    assert_eq!(bindings(&help.lines(&keymap)), [("U", Action::UpgradeAur)]);
+}
+
+#[tokio::test]
+async fn overlay_keys() {
+    let mut app = test_app(vec![
+        package("a", ItemRepo::Extra),
+        package("b", ItemRepo::Extra),
+    ]);
+    let selected = app.list_state.selected();
+    press(&mut app, KeyCode::Char('?'));
+    assert_eq!(app.help.as_ref().unwrap().mode, KeyMode::Normal);
+
+    // the overlay takes the keys while it is open
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.help.as_ref().unwrap().scroll, 2);
+    assert_eq!(app.list_state.selected(), selected);
+    press(&mut app, KeyCode::Char('G'));
+    let len = app.help.as_ref().unwrap().lines(&app.keymap).len();
+    assert_eq!(app.help.as_ref().unwrap().scroll, len - 1);
+
+    press(&mut app, KeyCode::Char('/'));
+    for c in "quit".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    let help = app.help.as_ref().unwrap();
+    assert_eq!((help.query.as_str(), help.scroll), ("quit", 0));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.help.is_none());
+    assert!(!app.exit);
+
+    press(&mut app, KeyCode::Char('i'));
+    press(&mut app, KeyCode::F(1));
+    assert_eq!(app.help.as_ref().unwrap().mode, KeyMode::Insert);
+    press(&mut app, KeyCode::Esc);
+    assert!(app.help.is_none());
+    assert!(app.insert_mode.enabled);
+}
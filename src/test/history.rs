@@ -0,0 +1,71 @@
+use std::{env, fs};
+
+use crate::objects::history::History;
+
+fn history(entries: &[&str]) -> History {
+    History::new(entries.iter().map(|e| e.to_string()).collect(), 3, None)
+}
+
+#[test]
+fn push_collapses_duplicates() {
+    let mut history = history(&["vim", "emacs"]);
+    history.push(" vim ").unwrap();
+    history.push("").unwrap();
+    assert_eq!(history.entries, ["emacs", "vim"]);
+    history.push("nano").unwrap();
+    history.push("helix").unwrap();
+    assert_eq!(history.entries, ["vim", "nano", "helix"]);
+}
+
+#[test]
+fn recall() {
+    let mut history = history(&["vim", "emacs"]);
+    assert_eq!(history.newer(), None);
+    assert_eq!(history.older("ne"), Some("emacs"));
+    assert_eq!(history.older("ne"), Some("vim"));
+    assert_eq!(history.older("ne"), None);
+    assert_eq!(history.newer(), Some("emacs"));
+    assert_eq!(history.newer(), Some("ne"));
+    assert_eq!(history.newer(), None);
+}
+
+#[test]
+fn reverse_search() {
+    let mut history = History::new(
+        ["vim-plug", "emacs", "vim", "neovim"]
+            .iter()
+            .map(|e| e.to_string())
+            .collect(),
+        10,
+        None,
+    );
+    history.start_search("typed");
+    history.search_push('v');
+    assert_eq!(history.search_match(), Some("neovim"));
+    history.search_push('i');
+    history.search_push('m');
+    assert_eq!(history.search_match(), Some("neovim"));
+    history.search_older();
+    assert_eq!(history.search_match(), Some("vim"));
+    history.search_push('-');
+    assert_eq!(history.search_match(), Some("vim-plug"));
+    history.search_push('x');
+    assert_eq!(history.search_match(), None);
+    assert_eq!(history.search_preview(), "typed");
+    history.search_pop();
+    assert_eq!(history.end_search(true), "vim-plug");
+
+    history.start_search("typed");
+    history.search_push('e');
+    assert_eq!(history.end_search(false), "typed");
+}
+
+#[test]
+fn persisted() {
+    let path = env::temp_dir().join(format!("pacseen-history-{}", std::process::id()));
+    let mut history = History::new(Vec::new(), 10, Some(path.clone()));
+    history.push("vim").unwrap();
+    history.push("emacs").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "vim\nemacs\n");
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,213 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    objects::{
+        config::Config,
+        keymap::{
+            Action, KeyChord, KeyConfig, KeyMode, KeyResult, Keymap, format_keys, parse_keys,
+        },
+        stat::ItemRepo,
+    },
+    test::{package, test_app},
+};
+
+fn chord(c: char) -> KeyChord {
+    KeyChord::plain(KeyCode::Char(c))
+}
+
+fn keys_config(text: &str) -> KeyConfig {
+    toml::from_str::<Config>(text).unwrap().keys
+}
+
+#[test]
+fn notation() {
+    let ctrl_r = KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+    assert_eq!(parse_keys("gg").unwrap(), [chord('g'), chord('g')]);
+    assert_eq!(parse_keys("<C-r>").unwrap(), [ctrl_r]);
+    assert_eq!(parse_keys("<c-r>").unwrap(), [ctrl_r]);
+    assert_eq!(
+        parse_keys("<S-Tab><Space><lt>").unwrap(),
+        [KeyChord::plain(KeyCode::BackTab), chord(' '), chord('<')]
+    );
+    assert_eq!(parse_keys("<S-a>").unwrap(), [chord('A')]);
+    assert!(parse_keys("<Hyper-x>").is_err());
+    assert!(parse_keys("<nokey>").is_err());
+    assert!(parse_keys("").is_err());
+
+    for text in [
+        "gg", "<C-r>", "<Enter>", "<A-b>", "<S-Tab>", "<Space>", "G", "<F5>",
+    ] {
+        assert_eq!(format_keys(&parse_keys(text).unwrap()), text);
+    }
+    // crossterm reports shifted letters with the shift modifier
+    let shifted = KeyChord::from(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT));
+    assert_eq!(shifted, chord('G'));
+}
+
+#[test]
+fn sequences() {
+    let mut keymap = Keymap::default();
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('g')),
+        [KeyResult::Pending]
+    );
+    assert_eq!(keymap.pending(), [chord('g')]);
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('g')),
+        [KeyResult::Action(Action::SelectFirst)]
+    );
+    assert!(keymap.pending().is_empty());
+
+    // a key breaking off the sequence still does its own thing
+    keymap.press(KeyMode::Normal, chord('g'));
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('j')),
+        [
+            KeyResult::Unbound(chord('g')),
+            KeyResult::Action(Action::SelectNext)
+        ]
+    );
+    assert_eq!(
+        keymap.press(KeyMode::Insert, chord('j')),
+        [KeyResult::Unbound(chord('j'))]
+    );
+}
+
+#[test]
+fn config_overrides_defaults() {
+    let keys = keys_config(
+        r#"
+        [keys.normal]
+        "g" = "select-last"
+        "<C-n>" = "select-next"
+        "q" = "unbound"
+        [keys.insert]
+        "<C-a>" = "cursor-left"
+        "#,
+    );
+    let mut keymap = Keymap::new(&keys).unwrap();
+    let ctrl_n = KeyChord::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('g')),
+        [KeyResult::Action(Action::SelectLast)]
+    );
+    assert_eq!(
+        keymap.press(KeyMode::Normal, ctrl_n),
+        [KeyResult::Action(Action::SelectNext)]
+    );
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('j')),
+        [KeyResult::Action(Action::SelectNext)]
+    );
+    assert_eq!(
+        keymap.press(KeyMode::Normal, chord('q')),
+        [KeyResult::Unbound(chord('q'))]
+    );
+    assert!(
+        keymap
+            .bindings(KeyMode::Normal)
+            .iter()
+            .all(|(_, action)| *action != Action::Unbound)
+    );
+}
+
+#[test]
+fn shift_tab_from_the_terminal() {
+    let keys = keys_config(
+        r#"
+        [keys.normal]
+        "<S-Tab>" = "select-previous"
+        "#,
+    );
+    let mut keymap = Keymap::new(&keys).unwrap();
+    // what crossterm sends for shift-tab
+    let key = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+    assert_eq!(
+        keymap.press(KeyMode::Normal, KeyChord::from(key)),
+        [KeyResult::Action(Action::SelectPrevious)]
+    );
+}
+
+#[test]
+fn conflicts_are_reported() {
+    let keys = keys_config(
+        r#"
+        [keys.normal]
+        "d" = "quit"
+        "dd" = "select-last"
+        "#,
+    );
+    let err = Keymap::new(&keys).unwrap_err();
+    assert!(err.contains("[keys.normal]"), "{}", err);
+    assert!(err.contains("d (quit) and dd (select-last)"), "{}", err);
+
+    let keys = keys_config(
+        r#"
+        [keys.insert]
+        "<C-x>" = "cursor-left"
+        "<c-x>" = "cursor-right"
+        "#,
+    );
+    assert!(Keymap::new(&keys).is_err());
+
+    assert!(toml::from_str::<Config>("[keys.normal]\nx = \"fly\"").is_err());
+}
+
+#[test]
+fn insert_actions_stay_in_the_insert_table() {
+    for action in ["cursor-left", "yank", "history-older", "submit-search"] {
+        let keys = keys_config(&format!("[keys.normal]\n\"<C-x>\" = \"{}\"", action));
+        let err = Keymap::new(&keys).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "[keys.normal] <C-x>: {} is only available in [keys.insert]",
+                action
+            )
+        );
+    }
+    let keys = keys_config("[keys.insert]\n\"<C-x>\" = \"yank\"");
+    assert!(Keymap::new(&keys).is_ok());
+}
+
+#[test]
+fn defaults_do_not_overlap() {
+    let keymap = Keymap::default();
+    for mode in [KeyMode::Normal, KeyMode::Insert] {
+        let bindings = keymap.bindings(mode);
+        for (i, (a, _)) in bindings.iter().enumerate() {
+            for (b, _) in &bindings[i + 1..] {
+                assert!(!a.starts_with(b) && !b.starts_with(a), "{:?} {:?}", a, b);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn keys_drive_the_app() {
+    let mut app = test_app(vec![
+        package("a", ItemRepo::Extra),
+        package("b", ItemRepo::Extra),
+        package("c", ItemRepo::Extra),
+    ]);
+    let press = |app: &mut crate::objects::stat::App, code: KeyCode| {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    };
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.list_state.selected(), Some(1));
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.list_state.selected(), Some(1));
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.list_state.selected(), Some(0));
+
+    press(&mut app, KeyCode::Char('m'));
+    assert!(app.marked.contains("a"));
+
+    press(&mut app, KeyCode::Char('i'));
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.search, "jb");
+    press(&mut app, KeyCode::Backspace);
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    assert!(app.history.search.is_some());
+}
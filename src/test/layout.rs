@@ -0,0 +1,200 @@
+use std::{
+    env,
+    fs::{self, File},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{Terminal, backend::TestBackend, layout::Rect};
+
+use crate::{
+    objects::{
+        config::Config,
+        keymap::{Action, KeyChord, KeyMode, KeyResult, Keymap},
+        layout::{LayoutConfig, Pane, PaneLayout},
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+    ui::render,
+};
+
+fn screen(app: &mut App, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn newer_config_wins() {
+    let dir = env::temp_dir().join(format!("pacseen-layout-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let (state, config_file) = (dir.join("layout.toml"), dir.join("config.toml"));
+    fs::write(&state, "split = 60\n").unwrap();
+    fs::write(&config_file, "[layout]\nsplit = 30\n").unwrap();
+    let config = LayoutConfig {
+        split: 30,
+        ..Default::default()
+    };
+    let touch = |path: &PathBuf, secs: u64| {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    };
+
+    // resized after the config was written
+    touch(&config_file, 1_000);
+    touch(&state, 2_000);
+    assert_eq!(
+        PaneLayout::load_from(&state, &config_file, &config).split,
+        60
+    );
+
+    // the config was edited since
+    touch(&config_file, 3_000);
+    assert_eq!(
+        PaneLayout::load_from(&state, &config_file, &config).split,
+        30
+    );
+
+    // no config at all
+    fs::remove_file(&config_file).unwrap();
+    assert_eq!(
+        PaneLayout::load_from(&state, &config_file, &config).split,
+        60
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn breakpoints() {
+    let config = LayoutConfig::default();
+    let mut layout = PaneLayout::new(&config);
+
+    // side by side, the notice below the info pane
+    let panes = layout.areas(Rect::new(0, 0, 120, 40), &config);
+    assert_eq!(panes.list, Rect::new(0, 0, 42, 40));
+    assert_eq!(panes.info, Rect::new(42, 0, 78, 37));
+    assert_eq!(panes.notice, Rect::new(42, 37, 78, 3));
+
+    // stacked, the notice below both
+    let panes = layout.areas(Rect::new(0, 0, 80, 40), &config);
+    assert_eq!(panes.list, Rect::new(0, 0, 80, 13));
+    assert_eq!(panes.info, Rect::new(0, 13, 80, 24));
+    assert_eq!(panes.notice, Rect::new(0, 37, 80, 3));
+
+    // too narrow for the info pane
+    let panes = layout.areas(Rect::new(0, 0, 50, 40), &config);
+    assert_eq!(panes.list, Rect::new(0, 0, 50, 37));
+    assert!(panes.info.is_empty());
+
+    // a maximized pane shows at any width
+    layout.toggle_maximized(Pane::Info);
+    let panes = layout.areas(Rect::new(0, 0, 50, 40), &config);
+    assert!(panes.list.is_empty());
+    assert_eq!(panes.info, Rect::new(0, 0, 50, 37));
+    layout.toggle_maximized(Pane::List);
+    let panes = layout.areas(Rect::new(0, 0, 120, 40), &config);
+    assert_eq!(panes.list, Rect::new(0, 0, 120, 37));
+    assert!(panes.info.is_empty());
+    layout.toggle_maximized(Pane::List);
+    assert_eq!(layout.maximized, None);
+}
+
+#[test]
+fn resizing() {
+    let config = toml::from_str::<Config>(
+        r#"
+        [layout]
+        split = 90
+        notice_height = 0
+        "#,
+    )
+    .unwrap()
+    .layout;
+    assert_eq!(config.stack_below, LayoutConfig::default().stack_below);
+    // the configured split is kept within bounds too
+    let mut layout = PaneLayout::new(&config);
+    assert_eq!(layout.split, 80);
+    layout.resize(-3);
+    assert_eq!(layout.split, 65);
+    layout.resize(-20);
+    assert_eq!(layout.split, 20);
+
+    let panes = layout.areas(Rect::new(0, 0, 100, 30), &config);
+    assert_eq!(panes.list.width, 20);
+    assert_eq!(panes.info.height, 30);
+    assert!(panes.notice.is_empty());
+
+    let saved = toml::to_string(&layout).unwrap();
+    assert_eq!(toml::from_str::<PaneLayout>(&saved).unwrap(), layout);
+
+    // a maximized pane is not kept
+    layout.toggle_maximized(Pane::Info);
+    let saved = toml::to_string(&layout).unwrap();
+    assert_eq!(saved, "split = 20\n");
+    assert_eq!(
+        toml::from_str::<PaneLayout>(&saved).unwrap().maximized,
+        None
+    );
+    assert_eq!(
+        toml::from_str::<PaneLayout>("split = 40\nmaximized = \"info\"\n")
+            .unwrap()
+            .maximized,
+        None
+    );
+}
+
+#[test]
+fn layout_keys() {
+    let mut keymap = Keymap::default();
+    let ctrl_w = KeyChord::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    for (key, action) in [
+        ('>', Action::GrowList),
+        ('<', Action::ShrinkList),
+        ('=', Action::ResetLayout),
+        ('o', Action::MaximizeList),
+        ('i', Action::MaximizeInfo),
+    ] {
+        assert_eq!(keymap.press(KeyMode::Normal, ctrl_w), [KeyResult::Pending]);
+        assert_eq!(
+            keymap.press(KeyMode::Normal, KeyChord::plain(KeyCode::Char(key))),
+            [KeyResult::Action(action)]
+        );
+    }
+}
+
+#[tokio::test]
+async fn narrow_terminals() {
+    let mut app = test_app(vec![package("vim", ItemRepo::Extra)]);
+    app.notice.text = "ready".to_string();
+
+    // stacked, the list above the info pane and the notice below both
+    let lines = screen(&mut app, 80, 30);
+    assert!(lines[3].starts_with("╭Packages"));
+    assert!(lines[9].starts_with("╭ Info"));
+    assert!(lines[28].starts_with("│ready"));
+    assert_eq!(app.areas.info.width, 78);
+
+    // only the list and the notice
+    let lines = screen(&mut app, 50, 30);
+    assert!(lines.iter().all(|line| !line.contains("Info")));
+    assert!(lines[28].starts_with("│ready"));
+    assert!(app.areas.info.is_empty());
+
+    app.layout.maximized = Some(Pane::Info);
+    let lines = screen(&mut app, 50, 30);
+    assert!(lines[0].starts_with("╭ Info"));
+    assert!(lines.iter().all(|line| !line.contains("Packages")));
+}
@@ -0,0 +1,93 @@
+use crate::{
+    objects::{
+        matcher::{MatchKind, fuzzy_match, regex_keyword},
+        stat::ItemRepo,
+    },
+    test::{package, test_app},
+};
+
+fn kind(name: &str, query: &str) -> Option<MatchKind> {
+    fuzzy_match(name, query, true).map(|m| m.kind)
+}
+
+#[test]
+fn match_kinds() {
+    assert_eq!(kind("firefox", "firefox"), Some(MatchKind::Exact));
+    assert_eq!(
+        kind("firefox-developer-edition", "firefox"),
+        Some(MatchKind::Prefix)
+    );
+    assert_eq!(
+        kind("python-requests", "requests"),
+        Some(MatchKind::WordBoundary)
+    );
+    assert_eq!(kind("libreoffice", "office"), Some(MatchKind::Substring));
+    assert_eq!(
+        kind("python-requests", "pyreq"),
+        Some(MatchKind::Subsequence)
+    );
+    assert_eq!(kind("vim", "emacs"), None);
+}
+
+#[test]
+fn match_positions() {
+    let m = fuzzy_match("python-requests", "pr", true).unwrap();
+    assert_eq!(m.positions, [0, 7]);
+    let m = fuzzy_match("lib32-requests-requests", "requests", true).unwrap();
+    assert_eq!(m.positions, (6..14).collect::<Vec<_>>());
+}
+
+#[test]
+fn smart_case() {
+    assert!(fuzzy_match("NetworkManager", "network", true).is_some());
+    assert!(fuzzy_match("networkmanager", "Network", true).is_none());
+    assert!(fuzzy_match("NetworkManager", "network", false).is_none());
+    assert!(fuzzy_match("NetworkManager", "Network", false).is_some());
+}
+
+#[tokio::test]
+async fn results_are_ranked() {
+    let mut app = test_app(
+        [
+            "firefox-developer-edition",
+            "firefox",
+            "librefox",
+            "python-firefox-driver",
+            "fire-fox-tools",
+            "xfce4-whiskermenu",
+        ]
+        .iter()
+        .map(|n| package(n, ItemRepo::Extra))
+        .collect(),
+    );
+    app.search = "firefox".to_string();
+    app.update_filter_local();
+
+    let names: Vec<&str> = app.filtered_packages().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "firefox",
+            "firefox-developer-edition",
+            "python-firefox-driver",
+            "fire-fox-tools"
+        ]
+    );
+    assert_eq!(app.filtered[0].positions, (0..7).collect::<Vec<_>>());
+}
+
+#[test]
+fn regex_keywords() {
+    assert_eq!(regex_keyword("^python-.*-git$"), "python-");
+    assert_eq!(regex_keyword("lib(32)?-?gtk[34]"), "gtk");
+    assert_eq!(regex_keyword("firefox\\.desktop"), "firefox.desktop");
+    assert_eq!(regex_keyword("vims?"), "vim");
+    assert_eq!(regex_keyword("(vim|emacs)"), "");
+    assert_eq!(regex_keyword("^x"), "");
+    // an alternation inside a group leaves the rest required
+    assert_eq!(regex_keyword("^python-(requests|httpx)$"), "python-");
+    // a lazy quantifier only makes the one character optional
+    assert_eq!(regex_keyword("neovims*?"), "neovim");
+    assert_eq!(regex_keyword("qt5{1,2}-base"), "-base");
+    assert_eq!(regex_keyword("firefoxy{0,1}"), "firefox");
+}
@@ -0,0 +1,112 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{Terminal, backend::TestBackend};
+
+use crate::{
+    objects::{
+        mouse::ContextMenu,
+        stat::{App, InfoTab, ItemRepo},
+        suggest::Suggestion,
+    },
+    test::{package, test_app},
+    ui::render,
+};
+
+fn app() -> App {
+    let pkgs = (0..10)
+        .map(|i| package(&format!("pkg{}", i), ItemRepo::Extra))
+        .collect();
+    let mut app = test_app(pkgs);
+    draw(&mut app);
+    app
+}
+
+// the rows of the list start at line 5 of a 120x40 screen, below the table
+// header, the info pane at column 42
+fn draw(app: &mut App) {
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+}
+
+fn mouse(app: &mut App, kind: MouseEventKind, column: u16, row: u16) {
+    app.handle_mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    });
+    draw(app);
+}
+
+fn click(app: &mut App, column: u16, row: u16) {
+    mouse(app, MouseEventKind::Down(MouseButton::Left), column, row);
+}
+
+#[tokio::test]
+async fn click_and_wheel() {
+    let mut app = app();
+    click(&mut app, 5, 7);
+    assert_eq!(app.list_state.selected(), Some(2));
+    mouse(&mut app, MouseEventKind::ScrollDown, 5, 10);
+    assert_eq!(app.list_state.selected(), Some(5));
+    mouse(&mut app, MouseEventKind::ScrollUp, 5, 10);
+    mouse(&mut app, MouseEventKind::ScrollUp, 5, 10);
+    assert_eq!(app.list_state.selected(), Some(0));
+
+    // over the info pane the wheel scrolls the open tab
+    mouse(&mut app, MouseEventKind::ScrollDown, 80, 10);
+    assert_eq!(app.info_scroll(InfoTab::Info), 3);
+    assert_eq!(app.list_state.selected(), Some(0));
+
+    click(&mut app, 5, 1);
+    assert!(app.insert_mode.enabled);
+}
+
+#[tokio::test]
+async fn click_tabs() {
+    let mut app = app();
+    let (tab, area) = app.areas.tabs[2];
+    assert_eq!(tab, InfoTab::Files);
+    click(&mut app, area.x + 1, area.y);
+    assert_eq!(app.info_tab, InfoTab::Files);
+    let (_, area) = app.areas.tabs[0];
+    click(&mut app, area.x, area.y);
+    assert_eq!(app.info_tab, InfoTab::Info);
+}
+
+#[tokio::test]
+async fn double_click() {
+    let mut app = app();
+    app.search = "pkgx".to_string();
+    app.update_filter_local();
+    assert!(app.filtered.is_empty());
+    app.suggestions = vec![Suggestion {
+        name: "pkg1".to_string(),
+        provided: false,
+        distance: 1,
+    }];
+    draw(&mut app);
+    click(&mut app, 5, 5);
+    assert_eq!(app.search, "pkgx");
+    click(&mut app, 5, 5);
+    assert_eq!(app.search, "pkg1");
+}
+
+#[tokio::test]
+async fn context_menu() {
+    let mut app = app();
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Right), 5, 8);
+    assert_eq!(app.list_state.selected(), Some(3));
+    let menu = app.areas.menu;
+    assert_eq!(menu.height as usize, ContextMenu::ACTIONS.len() + 2);
+
+    // the list does not take clicks while the menu is open
+    click(&mut app, 5, 30);
+    assert!(app.context_menu.is_none());
+    assert_eq!(app.list_state.selected(), Some(3));
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Right), 5, 8);
+    let menu = app.areas.menu;
+    click(&mut app, menu.x + 2, menu.y + 2);
+    assert!(app.marked.contains("pkg3"));
+    assert!(app.context_menu.is_none());
+}
@@ -0,0 +1,230 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{Terminal, backend::TestBackend, layout::Rect};
+
+use crate::{
+    objects::{
+        find::find_row,
+        keymap::{Action, KeyChord, KeyMode, KeyResult, Keymap},
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+    ui::render,
+};
+
+// 50 packages in a list 10 rows tall, with the first one selected
+fn app() -> App {
+    let pkgs = (0..50)
+        .map(|i| package(&format!("pkg{:02}", i), ItemRepo::Extra))
+        .collect();
+    let mut app = test_app(pkgs);
+    app.areas.list = Rect::new(1, 4, 40, 10);
+    app.list_state.select(Some(0));
+    app
+}
+
+fn keys(app: &mut App, keys: &str) {
+    for c in keys.chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+}
+
+fn ctrl(app: &mut App, c: char) {
+    app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+}
+
+fn selected(app: &App) -> usize {
+    app.list_state.selected().unwrap()
+}
+
+#[test]
+fn counts() {
+    let mut keymap = Keymap::default();
+    let press = |keymap: &mut Keymap, c: char| {
+        keymap.press(KeyMode::Normal, KeyChord::plain(KeyCode::Char(c)))
+    };
+    assert_eq!(press(&mut keymap, '1'), [KeyResult::Pending]);
+    assert_eq!(press(&mut keymap, '0'), [KeyResult::Pending]);
+    assert_eq!(keymap.count(), Some(10));
+    assert_eq!(
+        press(&mut keymap, 'j'),
+        [KeyResult::Action(Action::SelectNext)]
+    );
+    assert_eq!(keymap.take_count(), Some(10));
+    assert_eq!(keymap.count(), None);
+
+    // a leading 0 is no count, an unbound key drops the count
+    assert_eq!(
+        press(&mut keymap, '0'),
+        [KeyResult::Unbound(KeyChord::plain(KeyCode::Char('0')))]
+    );
+    press(&mut keymap, '3');
+    press(&mut keymap, 'x');
+    assert_eq!(keymap.count(), None);
+    // no counts while typing a search
+    assert_eq!(
+        keymap.press(KeyMode::Insert, KeyChord::plain(KeyCode::Char('3'))),
+        [KeyResult::Unbound(KeyChord::plain(KeyCode::Char('3')))]
+    );
+}
+
+#[tokio::test]
+async fn counted_motions() {
+    let mut app = app();
+    keys(&mut app, "5j");
+    assert_eq!(selected(&app), 5);
+    keys(&mut app, "12j");
+    assert_eq!(selected(&app), 17);
+    keys(&mut app, "3k");
+    assert_eq!(selected(&app), 14);
+    keys(&mut app, "99j");
+    assert_eq!(selected(&app), 49);
+    keys(&mut app, "20G");
+    assert_eq!(selected(&app), 19);
+    keys(&mut app, "3gg");
+    assert_eq!(selected(&app), 2);
+    keys(&mut app, "2]");
+    assert_eq!(app.info_tab, crate::objects::stat::InfoTab::Files);
+}
+
+#[tokio::test]
+async fn paging() {
+    let mut app = app();
+    ctrl(&mut app, 'd');
+    assert_eq!((selected(&app), app.list_state.offset()), (5, 5));
+    ctrl(&mut app, 'f');
+    assert_eq!((selected(&app), app.list_state.offset()), (15, 15));
+    keys(&mut app, "3");
+    ctrl(&mut app, 'f');
+    // the view stops at the last page, the selection at the last row
+    assert_eq!((selected(&app), app.list_state.offset()), (45, 40));
+    ctrl(&mut app, 'f');
+    assert_eq!((selected(&app), app.list_state.offset()), (49, 40));
+    ctrl(&mut app, 'u');
+    assert_eq!((selected(&app), app.list_state.offset()), (44, 35));
+    ctrl(&mut app, 'b');
+    assert_eq!((selected(&app), app.list_state.offset()), (34, 25));
+}
+
+#[tokio::test]
+async fn viewport_jumps() {
+    let mut app = app();
+    *app.list_state.offset_mut() = 20;
+    app.list_state.select(Some(25));
+    keys(&mut app, "H");
+    assert_eq!(selected(&app), 20);
+    keys(&mut app, "L");
+    assert_eq!(selected(&app), 29);
+    keys(&mut app, "M");
+    assert_eq!(selected(&app), 24);
+    keys(&mut app, "3H");
+    assert_eq!(selected(&app), 22);
+    keys(&mut app, "2L");
+    assert_eq!(selected(&app), 28);
+
+    keys(&mut app, "zz");
+    assert_eq!(app.list_state.offset(), 23);
+    assert_eq!(selected(&app), 28);
+
+    // a short last page ends at the last row
+    *app.list_state.offset_mut() = 45;
+    keys(&mut app, "L");
+    assert_eq!(selected(&app), 49);
+    keys(&mut app, "M");
+    assert_eq!(selected(&app), 47);
+}
+
+#[tokio::test]
+async fn section_headers_take_two_lines() {
+    let pkgs = (0..8)
+        .map(|i| match i < 4 {
+            true => package(&format!("repo{}", i), ItemRepo::Extra),
+            false => package(&format!("aur{}", i), ItemRepo::AUR(0)),
+        })
+        .collect();
+    let mut app = test_app(pkgs);
+    app.config.section_headers = true;
+    app.merge_results();
+    app.list_state.select(Some(0));
+    // the list keeps 5 lines for the results, 4 rows with one heading
+    let mut terminal = Terminal::new(TestBackend::new(50, 14)).unwrap();
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    assert_eq!(app.areas.list.height, 5);
+    assert_eq!(app.list_height(), 4);
+
+    ctrl(&mut app, 'f');
+    assert_eq!((selected(&app), app.list_state.offset()), (4, 4));
+    // the aur heading and 3 rows
+    assert_eq!(app.list_height(), 4);
+
+    keys(&mut app, "gg");
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    keys(&mut app, "G");
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    assert_eq!(app.list_state.offset(), 4);
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("aur7"));
+}
+
+#[test]
+fn find_rows() {
+    let names = ["vim", "neovim", "gvim", "emacs"];
+    assert_eq!(find_row(&names, "vim", 0, true), Some(1));
+    assert_eq!(find_row(&names, "vim", 2, true), Some(0));
+    assert_eq!(find_row(&names, "vim", 0, false), Some(2));
+    assert_eq!(find_row(&names, "emacs", 3, true), Some(3));
+    assert_eq!(find_row(&names, "VIM", 0, true), None);
+    assert_eq!(find_row(&names, "", 0, true), None);
+    assert_eq!(find_row(&[], "vim", 0, true), None);
+}
+
+#[tokio::test]
+async fn find_in_results() {
+    let mut app = app();
+    keys(&mut app, "/");
+    assert!(app.find.typing);
+    // keys go to the prompt, the selection follows the first match
+    keys(&mut app, "pkg1");
+    assert_eq!(selected(&app), 10);
+    keys(&mut app, "7");
+    assert_eq!(selected(&app), 17);
+    app.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(!app.find.typing);
+    assert_eq!(selected(&app), 10);
+
+    keys(&mut app, "n");
+    assert_eq!(selected(&app), 11);
+    keys(&mut app, "3n");
+    assert_eq!(selected(&app), 14);
+    keys(&mut app, "N");
+    assert_eq!(selected(&app), 13);
+    keys(&mut app, "G");
+    keys(&mut app, "n");
+    assert_eq!(selected(&app), 10);
+    assert!(app.notice.text.contains("continuing at the top"));
+
+    // Esc goes back to where the find started
+    keys(&mut app, "/pkg3");
+    assert_eq!(selected(&app), 30);
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(selected(&app), 10);
+    assert!(!app.find.typing);
+
+    // a paste goes where typing does
+    keys(&mut app, "/");
+    app.paste("pkg4");
+    assert_eq!(selected(&app), 40);
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(selected(&app), 10);
+
+    keys(&mut app, "/nothing");
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(selected(&app), 10);
+    assert!(app.notice.text.contains("not found"));
+}
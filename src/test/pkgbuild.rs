@@ -0,0 +1,122 @@
+use std::{env, fs, time::Duration};
+
+use ratatui::{Terminal, backend::TestBackend};
+
+use crate::{
+    backend::{
+        aur::AurClient,
+        pkgbuild::{fetch_aur_source, is_plain_name},
+    },
+    objects::{
+        stat::{InfoTab, ItemRepo, LoadError},
+        theme::Theme,
+    },
+    test::{
+        mock_aur::{MockAur, MockResponse},
+        package, test_app,
+    },
+    ui::{highlight::highlight_shell, render},
+};
+
+const PKGBUILD: &str = "\
+pkgname=paru # the helper
+build() {
+  cd \"$srcdir/${pkgname}\"
+  if true; then echo 'multi
+line'; fi
+}";
+
+#[test]
+fn highlighting() {
+    let syntax = Theme::default().syntax;
+    let lines = highlight_shell(PKGBUILD, &syntax);
+    assert_eq!(lines.len(), 6);
+
+    let spans = &lines[0].spans;
+    assert_eq!(
+        (spans[0].content.as_ref(), spans[0].style),
+        ("pkgname", syntax.assign)
+    );
+    assert_eq!(spans[1].content, "=paru ");
+    assert_eq!(
+        (spans[2].content.as_ref(), spans[2].style),
+        ("# the helper", syntax.comment)
+    );
+
+    assert_eq!(lines[1].spans[0].style, syntax.function);
+    let styled = |line: usize, text: &str| {
+        lines[line]
+            .spans
+            .iter()
+            .find(|s| s.content == text)
+            .map(|s| s.style)
+    };
+    assert_eq!(styled(2, "$srcdir"), Some(syntax.variable));
+    assert_eq!(styled(2, "${pkgname}"), Some(syntax.variable));
+    assert_eq!(styled(3, "if"), Some(syntax.keyword));
+    // a single quoted string carries over to the next line
+    assert_eq!(styled(4, "line'"), Some(syntax.string));
+    assert_eq!(styled(4, "fi"), Some(syntax.keyword));
+}
+
+#[test]
+fn package_names_stay_in_the_cache() {
+    assert!(is_plain_name("paru-bin"));
+    assert!(is_plain_name("python-3.12"));
+    for name in ["", ".", "..", "../etc", "a/b", "a\\b", ".hidden", "a..b"] {
+        assert!(!is_plain_name(name), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn plain_download_without_git() {
+    // the git clone fails against the mock, the files come from cgit instead
+    let mock = MockAur::start(|path| match path {
+        "/cgit/aur.git/plain/PKGBUILD?h=paru" => MockResponse::ok(PKGBUILD),
+        "/cgit/aur.git/plain/.SRCINFO?h=paru" => {
+            MockResponse::ok("pkgbase = paru\n\tinstall = paru.install\n")
+        }
+        "/cgit/aur.git/plain/paru.install?h=paru" => MockResponse::ok("post_install() {}\n"),
+        _ => MockResponse::ok("").status(404),
+    });
+    let client = AurClient::new(&mock.url, Duration::from_secs(2));
+    let cache = env::temp_dir().join(format!("pacseen-aur-{}", std::process::id()));
+
+    let source = fetch_aur_source(&client, "paru".to_string(), &cache)
+        .await
+        .unwrap();
+    assert_eq!(source.pkgbuild, PKGBUILD);
+    assert_eq!(source.files, [".SRCINFO", "PKGBUILD", "paru.install"]);
+    assert_eq!(source.dir, cache.join("paru"));
+
+    let err = fetch_aur_source(&client, "../paru".to_string(), &cache)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not a package name"));
+    fs::remove_dir_all(cache).unwrap();
+}
+
+#[tokio::test]
+async fn failed_fetch_is_shown() {
+    let mut app = test_app(vec![package("paru", ItemRepo::AUR(1))]);
+    app.info_tab = InfoTab::Pkgbuild;
+    app.load_failed(
+        InfoTab::Pkgbuild,
+        LoadError::new("paru", "connection refused"),
+    );
+
+    // not fetched again for the same package
+    app.load_info_tab();
+    assert!(app.source_task.is_none());
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+    terminal.draw(|frame| render(frame, &mut app)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("failed to fetch paru: connection refused"));
+}
@@ -0,0 +1,205 @@
+use crate::{
+    objects::{
+        query::{Cmp, Query, Term},
+        stat::{ItemRepo, Package},
+    },
+    test::package,
+};
+
+fn parse(input: &str) -> Query {
+    Query::parse(input).unwrap_or_else(|e| panic!("{}: {}", input, e))
+}
+
+fn term(term: Term) -> Query {
+    Query::Term(term)
+}
+
+fn text(t: &str) -> Query {
+    term(Term::Text(t.to_string()))
+}
+
+#[test]
+fn empty_query_matches_everything() {
+    assert_eq!(parse(""), Query::And(vec![]));
+    assert_eq!(parse("   "), Query::And(vec![]));
+    assert!(parse("").matches(&package("vim", ItemRepo::Extra), true));
+}
+
+#[test]
+fn free_text() {
+    assert_eq!(parse("vim"), text("vim"));
+    assert_eq!(
+        parse("vim plugin"),
+        Query::And(vec![text("vim"), text("plugin")])
+    );
+    assert_eq!(parse("name:vim"), text("vim"));
+}
+
+#[test]
+fn filters() {
+    assert_eq!(parse("repo:extra"), term(Term::Repo("extra".to_string())));
+    assert_eq!(parse("installed:yes"), term(Term::Installed(true)));
+    assert_eq!(parse("aur:no"), term(Term::Aur(false)));
+    assert_eq!(
+        parse("desc:compositor"),
+        term(Term::Desc("compositor".to_string()))
+    );
+    assert_eq!(
+        parse("provides:java-runtime"),
+        term(Term::Provides("java-runtime".to_string()))
+    );
+    assert_eq!(
+        parse(r#"desc:"tiling compositor""#),
+        term(Term::Desc("tiling compositor".to_string()))
+    );
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(parse("size>50M"), term(Term::Size(Cmp::Gt, 50 * 1024)));
+    assert_eq!(
+        parse("size<=1.5GiB"),
+        term(Term::Size(Cmp::Le, 1536 * 1024))
+    );
+    assert_eq!(parse("size<300k"), term(Term::Size(Cmp::Lt, 300)));
+    assert_eq!(parse("size=2048"), term(Term::Size(Cmp::Eq, 2)));
+    assert_eq!(parse("votes>100"), term(Term::Votes(Cmp::Gt, 100)));
+    assert_eq!(parse("votes>=5"), term(Term::Votes(Cmp::Ge, 5)));
+}
+
+#[test]
+fn boolean_operators() {
+    assert_eq!(
+        parse("vim -git"),
+        Query::And(vec![text("vim"), Query::Not(Box::new(text("git")))])
+    );
+    assert_eq!(
+        parse("a AND b OR c"),
+        Query::Or(vec![Query::And(vec![text("a"), text("b")]), text("c")])
+    );
+    assert_eq!(
+        parse("repo:core (vim OR emacs)"),
+        Query::And(vec![
+            term(Term::Repo("core".to_string())),
+            Query::Or(vec![text("vim"), text("emacs")]),
+        ])
+    );
+    assert_eq!(
+        parse("-(aur:yes OR installed:yes)"),
+        Query::Not(Box::new(Query::Or(vec![
+            term(Term::Aur(true)),
+            term(Term::Installed(true)),
+        ])))
+    );
+}
+
+#[test]
+fn exclusions_match_substrings() {
+    let query = parse("-git");
+    let excluded = |name: &str, repo: ItemRepo| !query.matches(&package(name, repo), true);
+    assert!(excluded("vim-git", ItemRepo::Extra));
+    assert!(excluded("GitHub-cli", ItemRepo::Extra));
+    // the letters in order are not enough
+    assert!(!excluded("gstreamer-plugins-bad-libs", ItemRepo::Extra));
+    assert!(!excluded("gimp-plugin-toolkit", ItemRepo::AUR(3)));
+
+    // aur rows are also excluded by their description
+    let mut aur = package("neovim-nightly", ItemRepo::AUR(3));
+    aur.descipt = "built from the git tree".to_string();
+    assert!(!query.matches(&aur, true));
+    // the repo search never looked at descriptions
+    let mut repo = package("tig", ItemRepo::Extra);
+    repo.descipt = "text-mode interface for git".to_string();
+    assert!(query.matches(&repo, true));
+
+    // positive terms still match fuzzily, a double negation too
+    assert!(parse("gst").matches(&package("gstreamer", ItemRepo::Extra), true));
+    assert!(!parse("vim -(-git)").matches(&package("vim-gtk", ItemRepo::Extra), true));
+}
+
+#[test]
+fn syntax_errors() {
+    let cases = [
+        ("foo:bar", 0, "unknown filter 'foo'"),
+        ("vim repo:", 4, "missing value for 'repo'"),
+        ("installed:maybe", 0, "installed: expects yes or no"),
+        ("size>lots", 0, "invalid size, expected e.g. 50M"),
+        ("votes>many", 0, "invalid number of votes"),
+        ("vim OR", 4, "OR needs a term on both sides"),
+        ("AND vim", 0, "AND needs a term on both sides"),
+        ("(vim", 0, "unmatched '('"),
+        ("vim)", 3, "unmatched ')'"),
+        ("vim -", 4, "nothing to exclude after '-'"),
+        (r#"desc:"open"#, 0, "unterminated quote"),
+    ];
+    for (input, position, message) in cases {
+        let err = Query::parse(input).unwrap_err();
+        assert_eq!(
+            (err.position, err.message.as_str()),
+            (position, message),
+            "{}",
+            input
+        );
+    }
+}
+
+fn sample() -> Vec<Package> {
+    let mut picom = package("picom", ItemRepo::Extra);
+    picom.descipt = "X compositor that may fix tearing issues".to_string();
+    picom.is_installed = true;
+    picom.size = 600;
+    let mut jdk = package("jdk-openjdk", ItemRepo::Extra);
+    jdk.provides = vec!["java-runtime".to_string(), "java-environment".to_string()];
+    jdk.size = 300 * 1024;
+    let mut yay = package("yay", ItemRepo::AUR(2500));
+    yay.descipt = "Yet another yogurt. Pacman wrapper and AUR helper written in go.".to_string();
+    vec![picom, jdk, yay, package("pacman", ItemRepo::Core)]
+}
+
+fn matching(query: &str) -> Vec<String> {
+    let query = parse(query);
+    sample()
+        .into_iter()
+        .filter(|p| query.matches(p, true))
+        .map(|p| p.name)
+        .collect()
+}
+
+#[test]
+fn evaluation() {
+    assert_eq!(matching("repo:extra"), ["picom", "jdk-openjdk"]);
+    assert_eq!(matching("installed:yes"), ["picom"]);
+    assert_eq!(matching("aur:yes"), ["yay"]);
+    assert_eq!(matching("aur:no installed:no"), ["jdk-openjdk", "pacman"]);
+    assert_eq!(matching("size>50M"), ["jdk-openjdk"]);
+    assert_eq!(matching("votes>100"), ["yay"]);
+    assert_eq!(matching("desc:COMPOSITOR"), ["picom"]);
+    assert_eq!(matching("provides:java-runtime"), ["jdk-openjdk"]);
+    assert_eq!(matching("pac -aur:yes"), ["pacman"]);
+    assert_eq!(matching("picom OR repo:core"), ["picom", "pacman"]);
+    // aur results also match on their description, like the aur search does
+    assert_eq!(matching("wrapper"), ["yay"]);
+}
+
+#[test]
+fn aur_keyword() {
+    assert_eq!(parse("vim plugin").aur_keyword(), "plugin");
+    assert_eq!(parse("vim -plugin").aur_keyword(), "vim");
+    assert_eq!(parse("v").aur_keyword(), "");
+    assert_eq!(parse("vim aur:no").aur_keyword(), "");
+    assert_eq!(parse("vim repo:extra").aur_keyword(), "");
+    assert_eq!(parse("vim repo:aur").aur_keyword(), "vim");
+    assert_eq!(parse("vim votes>10").aur_keyword(), "vim");
+}
+
+#[test]
+fn narrowing() {
+    assert!(parse("vim").narrows(&parse("")));
+    assert!(parse("vimp").narrows(&parse("vim")));
+    assert!(parse("vim pl").narrows(&parse("vim")));
+    assert!(!parse("vi").narrows(&parse("vim")));
+    assert!(!parse("vim -g").narrows(&parse("vim")));
+    assert!(!parse("vim -gi").narrows(&parse("vim -g")));
+    assert!(!parse("vim OR emacs").narrows(&parse("vim")));
+    assert_eq!(parse("vim (a OR b) repo:extra").required_text(), ["vim"]);
+}
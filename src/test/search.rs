@@ -0,0 +1,232 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    objects::stat::{AurResults, ItemRepo, SortKey},
+    test::{package, test_app},
+};
+
+fn names(app: &crate::objects::stat::App) -> Vec<&str> {
+    app.filtered_packages().map(|p| p.name.as_str()).collect()
+}
+
+fn aur(generation: u64, names: &[&str]) -> AurResults {
+    AurResults {
+        generation,
+        pkgs: Ok(names.iter().map(|n| package(n, ItemRepo::AUR(0))).collect()),
+    }
+}
+
+#[tokio::test]
+async fn stale_aur_results_are_dropped() {
+    let mut app = test_app(vec![package("vim", ItemRepo::Extra)]);
+
+    app.search = "vi".to_string();
+    app.update_filter_local();
+    let old = app.search_generation;
+    app.search = "vim".to_string();
+    app.update_filter_local();
+
+    assert!(!app.apply_aur_results(aur(old, &["vi-old"])));
+    assert_eq!(names(&app), ["vim"]);
+
+    assert!(app.apply_aur_results(aur(app.search_generation, &["vim-git"])));
+    assert_eq!(names(&app), ["vim", "vim-git"]);
+}
+
+#[tokio::test]
+async fn refiltering_keeps_aur_results() {
+    let mut app = test_app(vec![package("vim", ItemRepo::Extra)]);
+    app.search = "vim".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(aur(app.search_generation, &["vim-git"]));
+
+    // cursor movement and other keys re-filter without changing the query
+    app.update_filter_local();
+    assert_eq!(names(&app), ["vim", "vim-git"]);
+
+    app.search = "vi".to_string();
+    app.update_filter_local();
+    assert_eq!(names(&app), ["vim"]);
+}
+
+#[tokio::test]
+async fn merge_is_deterministic() {
+    let mut app = test_app(vec![
+        package("vim", ItemRepo::Extra),
+        package("vim-airline", ItemRepo::Extra),
+    ]);
+    app.search = "vim".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(aur(
+        app.search_generation,
+        &["vim-plug", "vim-git", "vim-plug", "vim-ale"],
+    ));
+
+    // one ranking over both sources, shorter names first within a tier
+    assert_eq!(
+        names(&app),
+        ["vim", "vim-ale", "vim-git", "vim-plug", "vim-airline"]
+    );
+}
+
+#[tokio::test]
+async fn selection_follows_the_package() {
+    let mut app = test_app(vec![package("vim", ItemRepo::Extra)]);
+    app.search = "vim".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(aur(app.search_generation, &["vim-git"]));
+    app.list_state.select(Some(1));
+
+    app.apply_aur_results(aur(app.search_generation, &["a-vim", "vim-git"]));
+    assert_eq!(app.selected_package().unwrap().name, "vim-git");
+}
+
+#[tokio::test]
+async fn regex_mode_filters_and_keeps_results_on_errors() {
+    let mut app = test_app(vec![
+        package("python-requests", ItemRepo::Extra),
+        package("python-requests-git", ItemRepo::Extra),
+        package("requests", ItemRepo::Extra),
+    ]);
+    app.toggle_regex_mode();
+    app.search = "^python-.*-git$".to_string();
+    app.update_filter_local();
+    assert_eq!(names(&app), ["python-requests-git"]);
+    assert_eq!(app.last_search, "python-");
+
+    // an unfinished pattern keeps the previous results and reports the error
+    app.search = "^python-(".to_string();
+    app.update_filter_local();
+    assert!(app.regex_error.is_some());
+    assert_eq!(names(&app), ["python-requests-git"]);
+
+    app.search = "^python-(requests)$".to_string();
+    app.update_filter_local();
+    assert!(app.regex_error.is_none());
+    assert_eq!(names(&app), ["python-requests"]);
+    assert_eq!(app.filtered[0].positions, (0..15).collect::<Vec<_>>());
+
+    app.apply_aur_results(aur(
+        app.search_generation,
+        &["python-requests-aur", "python-httpx"],
+    ));
+    assert_eq!(names(&app), ["python-requests"]);
+
+    app.search = "^python-[a-z]+$".to_string();
+    app.update_filter_local();
+    assert_eq!(names(&app), ["python-requests", "python-httpx"]);
+}
+
+#[tokio::test]
+async fn toggling_regex_mode_keeps_the_search() {
+    let mut app = test_app(vec![
+        package("python-requests", ItemRepo::Extra),
+        package("python-httpx", ItemRepo::Extra),
+    ]);
+    app.insert_mode.enabled = true;
+    for c in "requests".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+    assert!(app.regex_mode);
+    assert_eq!(app.search, "requests");
+    assert_eq!(names(&app), ["python-requests"]);
+    assert_eq!(app.last_search, "requests");
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+    assert!(!app.regex_mode);
+    assert_eq!(app.search, "requests");
+    assert_eq!(names(&app), ["python-requests"]);
+}
+
+#[tokio::test]
+async fn narrowing_matches_a_fresh_search() {
+    let pkgs = [
+        "vim",
+        "vim-plug",
+        "neovim",
+        "gvim",
+        "vifm",
+        "nvim-lspconfig",
+    ];
+    let mut app = test_app(pkgs.iter().map(|n| package(n, ItemRepo::Extra)).collect());
+    for search in ["v", "vi", "vim", "vim-", "vim", "vi", "vi -vim", "vi"] {
+        app.search = search.to_string();
+        app.update_filter_local();
+        let mut fresh = test_app(pkgs.iter().map(|n| package(n, ItemRepo::Extra)).collect());
+        fresh.search = search.to_string();
+        fresh.update_filter_local();
+        assert_eq!(names(&app), names(&fresh), "{}", search);
+    }
+}
+
+#[tokio::test]
+async fn sort_orders() {
+    let sized = |name: &str, size: u64| {
+        let mut p = package(name, ItemRepo::Extra);
+        p.installed_size = size;
+        p
+    };
+    let mut app = test_app(vec![
+        sized("vim", 4000),
+        sized("gvim", 9000),
+        sized("vim-plug", 10),
+    ]);
+    app.search = "vim".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(aur(app.search_generation, &["vim-git"]));
+    assert_eq!(app.sort, SortKey::Relevance);
+    assert_eq!(names(&app), ["vim", "vim-git", "vim-plug", "gvim"]);
+
+    app.cycle_sort();
+    assert_eq!(app.sort, SortKey::Name);
+    assert_eq!(names(&app), ["gvim", "vim", "vim-git", "vim-plug"]);
+
+    // aur results have no installed size and stay last either way
+    app.cycle_sort();
+    assert_eq!(app.sort, SortKey::InstalledSize);
+    assert_eq!(names(&app), ["vim-plug", "vim", "gvim", "vim-git"]);
+    app.toggle_sort_direction();
+    assert_eq!(names(&app), ["gvim", "vim", "vim-plug", "vim-git"]);
+
+    app.sort = SortKey::Votes;
+    app.merge_results();
+    assert_eq!(names(&app)[0], "vim-git");
+}
+
+#[tokio::test]
+async fn aur_results_know_the_local_packages() {
+    let mut vim = package("vim", ItemRepo::Extra);
+    vim.is_installed = true;
+    vim.installed_version = Some("9.1-1".to_string());
+    let mut app = test_app(vec![vim, package("vim-plug", ItemRepo::Extra)]);
+    app.local.insert("vim-git".to_string(), "9.0-1".to_string());
+    app.search = "vim".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(aur(app.search_generation, &["vim", "vim-git", "vim-ale"]));
+
+    // the repo package wins a tie with the aur package of the same name
+    assert_eq!(
+        names(&app),
+        ["vim", "vim", "vim-ale", "vim-git", "vim-plug"]
+    );
+    let aur_vim = &app.aur_results[0];
+    assert_eq!(aur_vim.shadows.as_deref(), Some("extra"));
+    // the installed vim is the repo one
+    assert!(!aur_vim.is_installed);
+    assert_eq!(aur_vim.installed_version, None);
+    let vim_git = &app.aur_results[1];
+    assert!(vim_git.is_installed);
+    assert_eq!(vim_git.installed_version.as_deref(), Some("9.0-1"));
+    assert_eq!(vim_git.shadows, None);
+    assert!(!app.aur_results[2].is_installed);
+
+    app.config.section_headers = true;
+    app.merge_results();
+    assert_eq!(
+        names(&app),
+        ["vim", "vim-plug", "vim", "vim-ale", "vim-git"]
+    );
+}
@@ -0,0 +1,78 @@
+use crate::{
+    objects::{
+        stat::{ItemRepo, Package},
+        suggest::{edit_distance, suggest},
+    },
+    test::{package, test_app},
+};
+
+fn distance(word: &str, name: &str) -> Option<usize> {
+    edit_distance(&word.chars().collect::<Vec<_>>(), name, 3)
+}
+
+fn packages() -> Vec<Package> {
+    let mut jdk = package("jdk-openjdk", ItemRepo::Extra);
+    jdk.provides = vec!["java-runtime".to_string(), "java-environment".to_string()];
+    vec![
+        package("firefox", ItemRepo::Extra),
+        package("firejail", ItemRepo::Extra),
+        package("thunderbird", ItemRepo::Extra),
+        jdk,
+    ]
+}
+
+#[test]
+fn distances() {
+    assert_eq!(distance("firefox", "firefox"), Some(0));
+    assert_eq!(distance("fierfox", "firefox"), Some(1));
+    assert_eq!(distance("firfox", "firefox"), Some(1));
+    assert_eq!(distance("thunderbrd", "thunderbird"), Some(1));
+    assert_eq!(distance("vim", "thunderbird"), None);
+}
+
+#[test]
+fn suggestions() {
+    let names: Vec<String> = suggest(&packages(), "fierfox")
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, ["firefox"]);
+
+    let found = suggest(&packages(), "java-runtme");
+    assert_eq!(found[0].term(), "provides:java-runtime");
+    assert!(suggest(&packages(), "xy").is_empty());
+}
+
+#[tokio::test]
+async fn accepting_a_suggestion() {
+    let mut app = test_app(packages());
+    app.search = "fierfox -beta".to_string();
+    app.update_filter_local();
+    // the aur gets asked before anything is suggested
+    assert!(app.suggestions.is_empty());
+    app.apply_aur_results(crate::objects::stat::AurResults {
+        generation: app.search_generation,
+        pkgs: Ok(Vec::new()),
+    });
+    assert_eq!(app.suggestions[0].name, "firefox");
+
+    app.accept_suggestion(0);
+    assert_eq!(app.search, "firefox -beta");
+    assert!(app.suggestions.is_empty());
+}
+
+#[tokio::test]
+async fn the_suggested_word_gets_replaced() {
+    let mut app = test_app(packages());
+    // the misspelling is part of the excluded word too
+    app.search = "-fierfoxes fierfox".to_string();
+    app.update_filter_local();
+    app.apply_aur_results(crate::objects::stat::AurResults {
+        generation: app.search_generation,
+        pkgs: Ok(Vec::new()),
+    });
+    assert_eq!(app.suggestions[0].name, "firefox");
+
+    app.accept_suggestion(0);
+    assert_eq!(app.search, "-fierfoxes firefox");
+}
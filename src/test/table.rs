@@ -0,0 +1,95 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{Terminal, backend::TestBackend, text::Span};
+
+use crate::{
+    objects::{
+        columns::{Column, Columns},
+        config::Config,
+        stat::{App, ItemRepo},
+    },
+    test::{package, test_app},
+    ui::{render, table::truncate},
+};
+
+fn screen(app: &mut App, width: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, 20)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+#[test]
+fn truncation() {
+    let line = |spans: Vec<Span<'static>>, width| truncate(spans, width).to_string();
+    assert_eq!(line(vec![Span::raw("neovim")], 8), "neovim");
+    assert_eq!(line(vec![Span::raw("neovim-nightly")], 8), "neovim-…");
+    assert_eq!(
+        line(vec![Span::raw("neo"), Span::raw("vim-git")], 5),
+        "neov…"
+    );
+    // a wide character that does not fit is left out whole
+    assert_eq!(line(vec![Span::raw("日本語")], 4), "日…");
+    assert_eq!(line(vec![Span::raw("vim")], 0), "");
+}
+
+#[test]
+fn column_config() {
+    let config = toml::from_str::<Config>("columns = [\"votes\", \"name\"]").unwrap();
+    let mut columns = Columns::new(&config.columns);
+    assert_eq!(columns.shown(), [Column::Votes, Column::Name]);
+    assert_eq!(
+        columns.order[..3],
+        [Column::Votes, Column::Name, Column::Installed]
+    );
+    columns.toggle(Column::Size);
+    assert_eq!(columns.shown(), [Column::Votes, Column::Name, Column::Size]);
+    columns.toggle(Column::Votes);
+    columns.toggle(Column::Size);
+    columns.toggle(Column::Name);
+    // the last column stays
+    assert_eq!(columns.shown(), [Column::Name]);
+    assert!(toml::from_str::<Config>("columns = [\"colour\"]").is_err());
+    assert_eq!(Columns::default().shown(), Column::DEFAULT);
+}
+
+#[tokio::test]
+async fn table_rows() {
+    let mut long = package("python-very-long-package-name-git", ItemRepo::AUR(42));
+    long.version = "2.0.1-3".to_string();
+    let mut app = test_app(vec![package("vim", ItemRepo::Extra), long]);
+    let lines = screen(&mut app, 120);
+    assert!(lines[4].contains("Name"));
+    assert!(lines[4].contains("Version"));
+    assert!(lines[5].contains("vim") && lines[5].contains("1.0-1") && lines[5].contains("extra"));
+    assert!(lines[6].contains("python-very-long-pack…"));
+    assert!(lines[6].contains("2.0.1-3"));
+
+    // the column popup adds the votes after the configured columns
+    press(&mut app, KeyCode::Char('v'));
+    let votes = app
+        .columns
+        .order
+        .iter()
+        .position(|c| *c == Column::Votes)
+        .unwrap();
+    for _ in 0..votes {
+        press(&mut app, KeyCode::Char('j'));
+    }
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Char('v'));
+    assert!(app.column_popup.is_none());
+    assert!(app.columns.is_shown(Column::Votes));
+    let lines = screen(&mut app, 120);
+    assert!(lines[4].contains("Votes"));
+    assert!(lines[6].contains(" 42"));
+}
@@ -0,0 +1,65 @@
+use ratatui::style::{Color, Modifier};
+
+use crate::objects::{
+    config::Config,
+    stat::NoticeLevel,
+    theme::{Theme, ThemeConfig, ThemeName},
+};
+
+fn theme_config(text: &str) -> ThemeConfig {
+    toml::from_str::<Config>(text).unwrap().theme
+}
+
+#[test]
+fn built_in_themes() {
+    assert_eq!(Theme::default(), Theme::dark());
+    assert_ne!(Theme::dark(), Theme::light());
+    assert_ne!(Theme::dark(), Theme::high_contrast());
+    assert_eq!(Theme::built_in(ThemeName::Light), Theme::light());
+    let config = theme_config("[theme]\nbase = \"high-contrast\"");
+    assert_eq!(config.base, ThemeName::HighContrast);
+    assert_eq!(Theme::new(&config, false).unwrap(), Theme::high_contrast());
+}
+
+#[test]
+fn config_overrides() {
+    let config = theme_config(
+        "[theme]\nbase = \"light\"\nselected = { bg = \"#334155\", bold = true }\n\"repo.extra\" = { fg = \"green\" }",
+    );
+    let theme = Theme::new(&config, false).unwrap();
+    assert_eq!(theme.selected.bg, Some(Color::Rgb(0x33, 0x41, 0x55)));
+    assert!(theme.selected.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(theme.repo_style("extra").fg, Some(Color::Green));
+    assert_eq!(theme.repo_style("custom"), Theme::light().repo);
+    assert_eq!(theme.text, Theme::light().text);
+}
+
+#[test]
+fn bad_theme_config() {
+    let unknown = theme_config("[theme]\nsparkle = { fg = \"red\" }");
+    assert!(Theme::new(&unknown, false).unwrap_err().contains("sparkle"));
+    let color = theme_config("[theme]\ntext = { fg = \"sunset\" }");
+    assert!(Theme::new(&color, false).unwrap_err().contains("sunset"));
+    assert!(toml::from_str::<Config>("[theme]\ntext = { colour = \"red\" }").is_err());
+}
+
+#[test]
+fn no_color() {
+    let config = theme_config("[theme]\n\"repo.extra\" = { fg = \"green\" }");
+    let theme = Theme::new(&config, true).unwrap();
+    assert_eq!(theme.text.fg, None);
+    assert_eq!(theme.selected.bg, None);
+    assert!(theme.selected.add_modifier.contains(Modifier::REVERSED));
+    assert!(theme.matched.add_modifier.contains(Modifier::UNDERLINED));
+    assert_eq!(theme.repo_style("extra").fg, None);
+    assert_eq!(theme.syntax.keyword.fg, None);
+}
+
+#[test]
+fn notice_levels() {
+    let theme = Theme::dark();
+    assert_eq!(theme.notice(NoticeLevel::Info), theme.notice_info);
+    assert_eq!(theme.notice(NoticeLevel::Warning), theme.notice_warning);
+    assert_eq!(theme.notice(NoticeLevel::Error), theme.notice_error);
+    assert_ne!(theme.notice_info, theme.notice_error);
+}
@@ -0,0 +1,64 @@
+use crate::backend::{aur::AurRaw, updates::build_report};
+
+fn aur(name: &str, version: &str, out_of_date: Option<i64>) -> AurRaw {
+    serde_json::from_value(serde_json::json!({
+        "Name": name,
+        "Version": version,
+        "OutOfDate": out_of_date,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn update_report() {
+    let foreign = [
+        ("yay", "12.3.5-1"),
+        ("paru", "1.9.0-1"),
+        ("flagged", "1.0-1"),
+        ("local-only", "0.1-1"),
+        ("downgraded", "2.0-1"),
+    ]
+    .map(|(name, version)| (name.to_string(), version.to_string()))
+    .to_vec();
+    let report = build_report(
+        foreign,
+        vec![
+            aur("paru", "1.10.0-1", None),
+            aur("yay", "12.4.0-1", Some(1700000000)),
+            aur("flagged", "1.0-1", Some(1700000000)),
+            aur("downgraded", "1.9-1", None),
+        ],
+    );
+
+    assert_eq!(report.checked, 5);
+    // versions are compared like pacman does, not as strings
+    let updates: Vec<(&str, &str, &str)> = report
+        .updates
+        .iter()
+        .map(|u| {
+            (
+                u.name.as_str(),
+                u.local_version.as_str(),
+                u.aur_version.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        updates,
+        [
+            ("paru", "1.9.0-1", "1.10.0-1"),
+            ("yay", "12.3.5-1", "12.4.0-1")
+        ]
+    );
+    assert!(report.updates[1].out_of_date);
+    let flagged: Vec<&str> = report.flagged.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(flagged, ["flagged", "yay"]);
+    assert_eq!(report.missing, ["local-only"]);
+}
+
+#[test]
+fn empty_report() {
+    let report = build_report(Vec::new(), vec![aur("paru", "2.0-1", None)]);
+    assert_eq!(report.checked, 0);
+    assert!(report.updates.is_empty() && report.flagged.is_empty() && report.missing.is_empty());
+}
@@ -0,0 +1,233 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    objects::{
+        dialog::{Answer, Dialog, DialogEvent, DialogKind, Purpose},
+        stat::{App, Notice},
+    },
+    ui::{centered, edit::text_width},
+};
+
+const CACHE_OPTIONS: [&str; 3] = [
+    "keep the last versions of every package",
+    "drop all versions of uninstalled packages",
+    "clean the paru build cache",
+];
+
+impl App {
+    // installed packages are removed only after a yes
+    pub fn confirm_remove(&mut self, name: String) {
+        self.dialog = Some(Dialog::confirm(
+            "Remove",
+            format!(
+                "remove {} and its unneeded dependencies with paru -Rns?",
+                name
+            ),
+            Purpose::RemovePackage(name),
+        ));
+    }
+
+    pub fn open_batch(&mut self) {
+        if self.marked.is_empty() {
+            self.notice = Notice::warning("no packages marked, 'm' marks one");
+            return;
+        }
+        let install = self.marked_packages(false).len();
+        let remove = self.marked_packages(true).len();
+        let options = vec![
+            format!("install {} not installed", install),
+            format!("remove {} installed", remove),
+            "unmark all".to_string(),
+        ];
+        self.dialog = Some(Dialog::pick(
+            "Marked packages",
+            format!("{} packages are marked", self.marked.len()),
+            options,
+            Purpose::Batch,
+        ));
+    }
+
+    pub fn open_cache_cleanup(&mut self) {
+        let options = CACHE_OPTIONS.iter().map(|o| o.to_string()).collect();
+        self.dialog = Some(Dialog::pick(
+            "Package cache",
+            "what to clean up".to_string(),
+            options,
+            Purpose::CleanCache,
+        ));
+    }
+
+    pub fn handle_dialog_key(&mut self, key: KeyEvent) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        match dialog.handle_key(key) {
+            DialogEvent::Open => {}
+            DialogEvent::Cancelled => self.dialog = None,
+            DialogEvent::Answered(answer) => {
+                let purpose = dialog.purpose.clone();
+                self.dialog = None;
+                self.answer_dialog(purpose, answer);
+            }
+        }
+    }
+
+    // runs what the dialog asked about, or opens the next one
+    fn answer_dialog(&mut self, purpose: Purpose, answer: Answer) {
+        match (purpose, answer) {
+            (Purpose::RemovePackage(name), Answer::Yes) => {
+                self.remove_pack(&name);
+                self.resume_after_helper();
+            }
+            (Purpose::Batch, Answer::Picked(0)) => {
+                let suspended = self.install_marked();
+                if suspended {
+                    self.resume_after_helper();
+                }
+            }
+            (Purpose::Batch, Answer::Picked(1)) => {
+                let names = self.marked_packages(true);
+                if names.is_empty() {
+                    self.notice = Notice::warning("none of the marked packages is installed");
+                    return;
+                }
+                self.dialog = Some(Dialog::confirm(
+                    "Remove",
+                    format!("remove {} with paru -Rns?", names.join(", ")),
+                    Purpose::RemoveMarked,
+                ));
+            }
+            (Purpose::Batch, Answer::Picked(_)) => {
+                self.marked.clear();
+                self.notice = Notice::info("all packages unmarked");
+            }
+            (Purpose::RemoveMarked, Answer::Yes) => {
+                let suspended = self.remove_marked();
+                if suspended {
+                    self.resume_after_helper();
+                }
+            }
+            (Purpose::CleanCache, Answer::Picked(0)) => {
+                self.dialog = Some(Dialog::input(
+                    "Package cache",
+                    "versions of each package to keep".to_string(),
+                    "3",
+                    Purpose::KeepVersions,
+                ));
+            }
+            (Purpose::CleanCache, Answer::Picked(1)) => {
+                self.clean_cache("sudo", &["paccache", "-ruk0"]);
+                self.resume_after_helper();
+            }
+            (Purpose::CleanCache, Answer::Picked(_)) => {
+                self.clean_cache("paru", &["-Sc"]);
+                self.resume_after_helper();
+            }
+            (Purpose::KeepVersions, Answer::Text(text)) => {
+                // paccache removes without asking, so 0 would empty the
+                // cache of installed packages too
+                let error = match text.trim().parse::<u32>() {
+                    Ok(0) => "keep at least 1 version".to_string(),
+                    Ok(keep) => {
+                        self.clean_cache("sudo", &["paccache", "-r", "-k", &keep.to_string()]);
+                        self.resume_after_helper();
+                        return;
+                    }
+                    Err(_) => format!("{} is not a number", text),
+                };
+                // asked again until it is a usable number or dismissed
+                let mut dialog = Dialog::input(
+                    "Package cache",
+                    "versions of each package to keep".to_string(),
+                    &text,
+                    Purpose::KeepVersions,
+                );
+                dialog.error = Some(error);
+                self.dialog = Some(dialog);
+            }
+            _ => {}
+        }
+    }
+}
+
+// centered over everything else, as wide as the message needs within limits
+pub fn render_dialog(frame: &mut Frame, app: &App) {
+    let Some(dialog) = &app.dialog else {
+        return;
+    };
+    let theme = &app.theme;
+    let screen = frame.area();
+    let width = (text_width(&dialog.message) + 4).clamp(40, 70);
+    let inner_width = width.saturating_sub(2).max(1);
+    let message_rows = text_width(&dialog.message).div_ceil(inner_width).max(1);
+    let body_rows = match &dialog.kind {
+        DialogKind::Confirm { .. } | DialogKind::Input { .. } => 1,
+        DialogKind::Pick { options, .. } => options.len() as u16,
+    };
+    let error_rows = dialog.error.is_some() as u16;
+    let height = message_rows + body_rows + error_rows + 3;
+    let area = centered(screen, width, height);
+
+    let mut lines = vec![Line::styled(dialog.message.clone(), theme.text)];
+    lines.push(Line::raw(""));
+    let mut cursor = None;
+    match &dialog.kind {
+        DialogKind::Confirm { yes } => {
+            let button = |label: &'static str, focused: bool| match focused {
+                true => Span::styled(label, theme.selected),
+                false => Span::styled(label, theme.text),
+            };
+            lines.push(
+                Line::from(vec![
+                    button("[ Yes ]", *yes),
+                    Span::raw("  "),
+                    button("[ No ]", !*yes),
+                ])
+                .centered(),
+            );
+        }
+        DialogKind::Input { text, cursor: at } => {
+            let before: String = text.chars().take(*at).collect();
+            cursor = Some((
+                area.x + 1 + text_width(&before),
+                area.y + 1 + message_rows + 1,
+            ));
+            lines.push(Line::styled(text.clone(), theme.search_insert));
+        }
+        DialogKind::Pick { options, cursor } => {
+            for (i, option) in options.iter().enumerate() {
+                let style = match i == *cursor {
+                    true => theme.selected,
+                    false => theme.text,
+                };
+                lines.push(Line::styled(format!("{} {}", i + 1, option), style));
+            }
+        }
+    }
+    if let Some(error) = &dialog.error {
+        lines.push(Line::styled(error.clone(), theme.notice_error));
+    }
+    let hint = match &dialog.kind {
+        DialogKind::Confirm { .. } => "y/n, Esc cancels",
+        DialogKind::Input { .. } => "Enter accepts, Esc cancels",
+        DialogKind::Pick { .. } => "j/k and Enter or a number, Esc cancels",
+    };
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(dialog.title.as_str())
+            .title_bottom(Line::from(hint).centered())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+    if let Some(position) = cursor {
+        frame.set_cursor_position(position);
+    }
+}
@@ -0,0 +1,138 @@
+use std::io;
+
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+};
+use ratatui::text::Span;
+
+use crate::objects::{edit::EditKind, stat::App};
+
+// a paste arrives as one event instead of a key per character
+pub fn set_bracketed_paste(enabled: bool) {
+    let _ = match enabled {
+        true => execute!(io::stdout(), EnableBracketedPaste),
+        false => execute!(io::stdout(), DisableBracketedPaste),
+    };
+}
+
+// display width, a CJK character takes two columns
+pub fn text_width(text: &str) -> u16 {
+    Span::raw(text).width() as u16
+}
+
+impl App {
+    // every edit of the search box goes through here, the cursor ends up
+    // after the inserted text
+    fn replace_range(&mut self, start: usize, end: usize, with: &str, kind: EditKind) {
+        self.history.stop_recall();
+        self.edits
+            .record(kind, &self.search, self.insert_mode.index);
+        let before = self.search.chars().take(start);
+        let after = self.search.chars().skip(end);
+        self.search = before.chain(with.chars()).chain(after).collect();
+        self.insert_mode.index = start + with.chars().count();
+    }
+
+    pub fn enter_char(&mut self, new_char: char) {
+        let i = self.insert_mode.index;
+        self.replace_range(i, i, &new_char.to_string(), EditKind::Insert);
+    }
+
+    pub fn delete_char(&mut self) {
+        let i = self.insert_mode.index;
+        if i > 0 {
+            self.replace_range(i - 1, i, "", EditKind::Delete);
+        }
+    }
+
+    pub fn delete_forward(&mut self) {
+        let i = self.insert_mode.index;
+        if i < self.search.chars().count() {
+            self.replace_range(i, i + 1, "", EditKind::Delete);
+        }
+    }
+
+    // removes the chars from `start` to `end` into the kill buffer
+    pub fn kill(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        self.edits.killed = self.search.chars().skip(start).take(end - start).collect();
+        self.replace_range(start, end, "", EditKind::Kill);
+    }
+
+    pub fn yank(&mut self) {
+        let i = self.insert_mode.index;
+        let killed = self.edits.killed.clone();
+        if !killed.is_empty() {
+            self.replace_range(i, i, &killed, EditKind::Paste);
+        }
+    }
+
+    // line breaks of a pasted text become spaces, the search is one line.
+    // an open dialog or popup takes it like keys, only an input dialog uses
+    // it, and a reverse search extends its pattern
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        if let Some(dialog) = &mut self.dialog {
+            dialog.paste(&text);
+            return;
+        }
+        if self.help.is_some()
+            || self.context_menu.is_some()
+            || self.filter_popup.is_some()
+            || self.column_popup.is_some()
+        {
+            return;
+        }
+        if self.find.typing {
+            self.find.pattern.push_str(&text);
+            self.follow_find();
+            return;
+        }
+        if self.history.search.is_some() {
+            for c in text.chars() {
+                self.history.search_push(c);
+            }
+            let preview = self.history.search_preview().to_string();
+            self.set_search(preview);
+            self.update_filter_local();
+            return;
+        }
+        self.insert_mode.enabled = true;
+        let i = self.insert_mode.index;
+        self.replace_range(i, i, &text, EditKind::Paste);
+        self.update_filter_local();
+    }
+
+    pub fn undo(&mut self) {
+        if let Some((search, cursor)) = self.edits.undo() {
+            self.search = search;
+            self.insert_mode.index = cursor;
+        }
+    }
+
+    pub fn set_cursor(&mut self, index: usize) {
+        self.edits.break_run();
+        self.insert_mode.index = index.min(self.search.chars().count());
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        self.set_cursor(self.insert_mode.index.saturating_sub(1));
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        self.set_cursor(self.insert_mode.index.saturating_add(1));
+    }
+
+    // the column of the cursor within the search text
+    pub fn cursor_column(&self) -> u16 {
+        let before: String = self.search.chars().take(self.insert_mode.index).collect();
+        text_width(&before)
+    }
+}
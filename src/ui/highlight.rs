@@ -0,0 +1,165 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::objects::theme::Syntax;
+
+const KEYWORDS: [&str; 18] = [
+    "if", "then", "else", "elif", "fi", "for", "in", "do", "done", "case", "esac", "while",
+    "until", "function", "return", "local", "export", "declare",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+// a line based bash highlighter, good enough for PKGBUILDs and install scripts
+pub fn highlight_shell(src: &str, syntax: &Syntax) -> Vec<Line<'static>> {
+    let mut quote = Quote::None;
+    src.lines()
+        .map(|line| highlight_line(line, &mut quote, syntax))
+        .collect()
+}
+
+fn highlight_line(line: &str, quote: &mut Quote, syntax: &Syntax) -> Line<'static> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match *quote {
+            Quote::Single => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == '\'')
+                    .map(|p| i + p + 1);
+                let stop = end.unwrap_or(chars.len());
+                push(&mut spans, &chars[i..stop], syntax.string);
+                if end.is_some() {
+                    *quote = Quote::None;
+                }
+                i = stop;
+            }
+            Quote::Double => {
+                let start = i;
+                while i < chars.len() && chars[i] != '"' && chars[i] != '$' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = i.min(chars.len());
+                push(&mut spans, &chars[start..i], syntax.string);
+                if i < chars.len() {
+                    if chars[i] == '"' {
+                        push(&mut spans, &chars[i..i + 1], syntax.string);
+                        *quote = Quote::None;
+                        i += 1;
+                    } else {
+                        let end = variable_end(&chars, i);
+                        push(&mut spans, &chars[i..end], syntax.variable);
+                        i = end;
+                    }
+                }
+            }
+            Quote::None => {
+                let c = chars[i];
+                if c == '#' && (i == 0 || chars[i - 1].is_whitespace()) {
+                    flush(&mut spans, &mut plain);
+                    push(&mut spans, &chars[i..], syntax.comment);
+                    break;
+                } else if c == '\'' || c == '"' {
+                    flush(&mut spans, &mut plain);
+                    push(&mut spans, &chars[i..i + 1], syntax.string);
+                    *quote = if c == '\'' {
+                        Quote::Single
+                    } else {
+                        Quote::Double
+                    };
+                    i += 1;
+                } else if c == '$' {
+                    flush(&mut spans, &mut plain);
+                    let end = variable_end(&chars, i);
+                    push(&mut spans, &chars[i..end], syntax.variable);
+                    i = end;
+                } else if c == '\\' {
+                    plain.extend(&chars[i..(i + 2).min(chars.len())]);
+                    i += 2;
+                } else if is_word_char(c) && (i == 0 || !is_word_char(chars[i - 1])) {
+                    let end = word_end(&chars, i);
+                    let word: String = chars[i..end].iter().collect();
+                    let line_start = spans.is_empty() && plain.trim().is_empty();
+                    let style = if KEYWORDS.contains(&word.as_str()) {
+                        Some(syntax.keyword)
+                    } else if line_start && chars.get(end) == Some(&'=') {
+                        Some(syntax.assign)
+                    } else if line_start && is_function_def(&chars[end..]) {
+                        Some(syntax.function)
+                    } else {
+                        None
+                    };
+                    match style {
+                        Some(style) => {
+                            flush(&mut spans, &mut plain);
+                            spans.push(Span::styled(word, style));
+                        }
+                        None => plain.push_str(&word),
+                    }
+                    i = end;
+                } else {
+                    plain.push(c);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    flush(&mut spans, &mut plain);
+    Line::from(spans)
+}
+
+fn push(spans: &mut Vec<Span<'static>>, chars: &[char], style: Style) {
+    if !chars.is_empty() {
+        spans.push(Span::styled(chars.iter().collect::<String>(), style));
+    }
+}
+
+fn flush(spans: &mut Vec<Span<'static>>, plain: &mut String) {
+    if !plain.is_empty() {
+        spans.push(Span::raw(std::mem::take(plain)));
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn word_end(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|&c| !is_word_char(c))
+        .map_or(chars.len(), |p| start + p)
+}
+
+fn is_function_def(rest: &[char]) -> bool {
+    let rest: String = rest.iter().collect();
+    rest.trim_start().starts_with("()")
+}
+
+// `chars[start]` is the `$`
+fn variable_end(chars: &[char], start: usize) -> usize {
+    match chars.get(start + 1) {
+        Some('{') => chars[start..]
+            .iter()
+            .position(|&c| c == '}')
+            .map_or(chars.len(), |p| start + p + 1),
+        Some(&c) if c.is_alphabetic() || c == '_' => word_end(chars, start + 1),
+        Some(&c) if c.is_ascii_digit() || "@*#?$!-(".contains(c) => start + 2,
+        _ => start + 1,
+    }
+}
@@ -1,170 +1,880 @@
 use std::io;
 
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{EnterAlternateScreen, enable_raw_mode},
+};
 use ratatui::{
-    DefaultTerminal, Frame,
+    Frame,
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     prelude::Widget,
-    style::{Color, Modifier, Style, Stylize, palette::tailwind::SLATE},
+    style::{Style, Stylize},
     symbols::border,
-    text::Line,
+    text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, HighlightSpacing, List, ListItem, Padding, Paragraph,
-        StatefulWidget, Wrap,
+        Block, BorderType, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Padding,
+        Paragraph, Row, StatefulWidget, Table, TableState, Wrap,
     },
 };
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use tokio::time::sleep;
 
-use crate::objects::stat::{App, ItemRepo};
+use crate::backend::wait_for_enter;
+use crate::objects::edit::{rubout_start, word_left, word_right};
+use crate::objects::help::{Help, HelpLine};
+use crate::objects::keymap::{Action, KeyChord, KeyMode, KeyResult, format_keys};
+use crate::objects::layout::Pane;
+use crate::objects::mouse::{Areas, ContextMenu};
+use crate::objects::stat::{App, InfoTab, ItemRepo, Notice, NoticeLevel, Package};
+use crate::objects::theme::Theme;
+use crate::ui::dialog::render_dialog;
+use crate::ui::edit::{set_bracketed_paste, text_width};
+use crate::ui::highlight::highlight_shell;
+use crate::ui::mouse::set_mouse_capture;
+use crate::ui::table::{PackageRows, package_rows};
+
+pub mod dialog;
+pub mod edit;
+pub mod highlight;
+pub mod mouse;
+pub mod navigation;
+pub mod table;
 
-const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
-const TEXT_FG_COLOR: Color = SLATE.c200;
+// what ratatui::init does apart from installing its panic hook, which main
+// already did once
+fn enter_tui() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)
+}
 
 pub fn render<'a>(frame: &mut Frame<'a>, app: &mut App) {
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
-        .split(frame.area());
+    let panes = app.layout.areas(frame.area(), &app.config.layout);
 
+    let chips = app.filters.chips();
     let left_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)])
-        .split(main_chunks[0]);
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(if chips.is_empty() { 0 } else { 1 }),
+            Constraint::Min(0),
+        ])
+        .split(panes.list);
 
-    let right_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(3), Constraint::Length(3)])
-        .split(main_chunks[1]);
+    // only the rows that fit in the lines below the table header are built,
+    // the list gets a window of the results and a selection relative to it
+    let lines = left_chunks[2].height.saturating_sub(3);
+    let suggesting = app.filtered.is_empty() && !app.suggestions.is_empty();
+    let (offset, window_state) = match suggesting {
+        true => (0, suggestion_state(app)),
+        false => list_window(app, lines),
+    };
 
+    let theme = &app.theme;
+    let title = match (&app.history.search, app.regex_mode, app.fulltext_mode) {
+        (Some(search), _, _) => format!("History search: {}", search.pattern),
+        (None, true, _) => "Search (regex)".to_string(),
+        (None, false, true) => "Search (full text)".to_string(),
+        (None, false, false) => "Search".to_string(),
+    };
+    let mut search_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(border::HEAVY_QUADRUPLE_DASHED);
+    if app
+        .history
+        .search
+        .as_ref()
+        .is_some_and(|s| s.found.is_none() && !s.pattern.is_empty())
+    {
+        search_block = search_block.title_bottom(Line::from("no match").style(theme.search_error));
+    } else if let (true, Some(err)) = (app.regex_mode, &app.regex_error) {
+        search_block =
+            search_block.title_bottom(Line::from(err.as_str()).style(theme.search_error));
+    }
+    // scrolled sideways so a long search keeps the cursor in view
+    let search_scroll = app
+        .cursor_column()
+        .saturating_sub(left_chunks[0].width.saturating_sub(3));
     let search = Paragraph::new(app.search.as_str())
-        .block(
-            Block::default()
-                .title("Search")
-                .borders(Borders::ALL)
-                .border_set(border::HEAVY_QUADRUPLE_DASHED),
-        )
+        .block(search_block)
+        .scroll((0, search_scroll))
         .style(match app.insert_mode.enabled {
-            true => Style::default(),
-            false => Style::default().fg(Color::Yellow),
+            true => theme.search_insert,
+            false => theme.search,
         });
 
-    let items: Vec<ListItem> = app
-        .filtered
-        .iter()
-        .map(|p| {
-            let prefix = if p.is_installed { "●" } else { " " };
-
-            ListItem::new(format!(
-                "{} {:<24} [{}]",
-                prefix,
-                p.name,
-                match &p.repo {
-                    ItemRepo::Core => "core",
-                    ItemRepo::Extra => "extra",
-                    ItemRepo::Multilib => "multilib",
-                    ItemRepo::Archlinuxcn => "archlinuxcn",
-                    ItemRepo::AbsOther(string) => string,
-                    ItemRepo::AUR(_) => "aur",
-                }
-            ))
-        })
-        .collect();
-    let options = List::new(items)
+    // the table leaves a column for the highlight symbol
+    let table_width = left_chunks[2].width.saturating_sub(3);
+    let rows = app.rows_fitting(offset, lines as usize);
+    let mut table = package_rows(app, offset, rows, table_width);
+    let mut list_title = format!(
+        "Packages ({} {})",
+        app.sort.title(),
+        if app.sort_descending { "↓" } else { "↑" }
+    );
+    if suggesting {
+        list_title = "Nothing found, did you mean".to_string();
+        table = PackageRows {
+            header: Row::new(["Suggestion"]).style(theme.label),
+            rows: app
+                .suggestions
+                .iter()
+                .map(|s| match s.provided {
+                    true => Row::new([format!("{} (provided)", s.name)]),
+                    false => Row::new([s.name.clone()]),
+                })
+                .collect(),
+            widths: vec![Constraint::Fill(1)],
+            lines: (0..app.suggestions.len()).map(Some).collect(),
+        };
+    }
+    // the count and keys typed so far of a longer binding
+    let count = app.keymap.count().map(|n| n.to_string());
+    let pending = count.unwrap_or_default() + &format_keys(app.keymap.pending());
+    let find = match app.find.typing {
+        true => format!("/{}", app.find.pattern),
+        false => String::new(),
+    };
+    let row_lines = table.lines;
+    let list_inner = left_chunks[2].inner(Margin::new(1, 1));
+    let options = Table::new(table.rows, table.widths)
+        .header(table.header)
+        .column_spacing(1)
         .block(
             Block::default()
-                .title("Packages")
+                .title(list_title)
+                .title_bottom(Line::from(find))
+                .title_bottom(Line::from(pending).right_aligned())
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded),
         )
-        .highlight_style(SELECTED_STYLE)
+        .row_highlight_style(theme.selected)
         .highlight_symbol(">")
         .highlight_spacing(HighlightSpacing::Always)
-        .style(Style::new());
+        .style(theme.text);
 
-    let info = Paragraph::new("package info").block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .light_blue(),
-    );
+    let info = Block::default()
+        .title(tab_titles(app.info_tab, theme))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.border);
+    let info_area = info.inner(panes.info);
 
-    let notice = Paragraph::new(app.notice.clone()).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .light_blue(),
-    );
+    let notice = Paragraph::new(app.notice.text.clone())
+        .style(theme.notice(app.notice.level))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.border),
+        );
 
     frame.render_widget(search, left_chunks[0]);
+    let chips: Vec<Span> = chips
+        .into_iter()
+        .flat_map(|chip| {
+            [
+                Span::styled(format!(" {} ", chip), theme.chip),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+    frame.render_widget(Line::from(chips), left_chunks[1]);
+    let mut table_state = TableState::default().with_selected(window_state.selected());
     StatefulWidget::render(
         options,
-        left_chunks[1],
+        left_chunks[2],
         frame.buffer_mut(),
-        &mut app.list_state,
+        &mut table_state,
     );
-    frame.render_widget(info, right_chunks[0]);
-    app.render_selected_item(right_chunks[0], frame.buffer_mut());
-    frame.render_widget(notice, right_chunks[1]);
-    if app.insert_mode.enabled {
+    frame.render_widget(info, panes.info);
+    // a hidden pane is an empty rect
+    match app.info_tab {
+        _ if info_area.is_empty() => {}
+        InfoTab::Info => app.render_selected_item(info_area, frame.buffer_mut()),
+        InfoTab::Dependencies => app.render_package_deps(info_area, frame.buffer_mut()),
+        InfoTab::Files => app.render_package_files(info_area, frame.buffer_mut()),
+        InfoTab::History => app.render_package_history(info_area, frame.buffer_mut()),
+        InfoTab::Pkgbuild => app.render_aur_source(info_area, frame.buffer_mut()),
+        InfoTab::Comments => app.render_aur_comments(info_area, frame.buffer_mut()),
+        InfoTab::Updates => app.render_aur_updates(info_area, frame.buffer_mut()),
+    }
+    frame.render_widget(notice, panes.notice);
+    app.areas = Areas {
+        search: left_chunks[0],
+        // below the header row
+        list: Rect {
+            y: list_inner.y + 1,
+            height: list_inner.height.saturating_sub(1),
+            ..list_inner
+        },
+        rows: row_lines,
+        info: info_area,
+        tabs: tab_areas(panes.info),
+        menu: Rect::default(),
+    };
+    if let Some(menu) = &app.context_menu {
+        app.areas.menu = render_context_menu(frame, app, menu);
+    }
+    if let Some(cursor) = app.filter_popup {
+        render_filter_popup(frame, app, cursor);
+    }
+    if let Some(cursor) = app.column_popup {
+        render_column_popup(frame, app, cursor);
+    }
+    if app.dialog.is_some() {
+        render_dialog(frame, app);
+    } else if app.help.is_some() {
+        render_help(frame, app);
+    } else if app.find.typing {
+        let list = left_chunks[2];
+        frame.set_cursor_position((
+            list.x + text_width(&app.find.pattern) + 2,
+            list.bottom().saturating_sub(1),
+        ));
+    } else if app.insert_mode.enabled && !left_chunks[0].is_empty() {
         let original_x = left_chunks[0].x;
         let original_y = left_chunks[0].y;
 
         frame.set_cursor_position((
-            original_x + app.insert_mode.index as u16 + 1,
+            original_x + (app.cursor_column() - search_scroll) + 1,
             original_y + 1,
         ));
     }
 }
 
+fn render_filter_popup(frame: &mut Frame, app: &App, cursor: usize) {
+    let options = app.filter_options();
+    let items: Vec<ListItem> = options
+        .iter()
+        .map(|option| {
+            let mark = if app.filters.is_checked(option) {
+                "x"
+            } else {
+                " "
+            };
+            ListItem::new(format!("[{}] {}", mark, option.label()))
+        })
+        .collect();
+    let area = centered(frame.area(), 34, options.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title("Filters")
+                .title_bottom(Line::from("space toggles, f closes").centered())
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(app.theme.border),
+        )
+        .style(app.theme.text)
+        .highlight_style(app.theme.selected);
+    frame.render_widget(Clear, area);
+    let mut state = ListState::default().with_selected(Some(cursor));
+    StatefulWidget::render(list, area, frame.buffer_mut(), &mut state);
+}
+
+fn render_help(frame: &mut Frame, app: &mut App) {
+    let Some(help) = &app.help else {
+        return;
+    };
+    let theme = &app.theme;
+    let lines = help.lines(&app.keymap);
+    let area = centered(
+        frame.area(),
+        64,
+        (lines.len() as u16 + 2)
+            .max(5)
+            .min(frame.area().height.saturating_sub(2)),
+    );
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = help.scroll.min(lines.len().saturating_sub(height));
+    let mut text: Vec<Line> = lines
+        .iter()
+        .skip(scroll)
+        .take(height)
+        .map(|line| match line {
+            HelpLine::Category(category) => Line::styled(category.title(), theme.section_header),
+            HelpLine::Binding { keys, action } => Line::from(vec![
+                Span::styled(format!("  {:<18} ", keys), theme.label),
+                Span::styled(action.description(), theme.text),
+            ]),
+        })
+        .collect();
+    if lines.is_empty() {
+        text.push(Line::styled("no binding matches", theme.dim));
+    }
+    let mode = match help.mode {
+        KeyMode::Normal => "normal",
+        KeyMode::Insert => "insert",
+    };
+    let footer = match help.searching || !help.query.is_empty() {
+        true => format!("/{}", help.query),
+        false => "/ searches, j/k scroll, ? closes".to_string(),
+    };
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .title(format!("Keys in {} mode", mode))
+            .title_bottom(Line::from(footer).centered())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+    if help.searching {
+        let footer_x = area.x
+            + (area
+                .width
+                .saturating_sub(help.query.chars().count() as u16 + 1))
+                / 2;
+        frame.set_cursor_position((
+            footer_x + help.query.chars().count() as u16 + 1,
+            area.bottom().saturating_sub(1),
+        ));
+    }
+    if let Some(help) = &mut app.help {
+        help.scroll = scroll;
+    }
+}
+
+// drawn below the clicked cell and moved up or left to stay on screen
+fn render_context_menu(frame: &mut Frame, app: &App, menu: &ContextMenu) -> Rect {
+    let screen = frame.area();
+    let width = 34.min(screen.width);
+    let height = (ContextMenu::ACTIONS.len() as u16 + 2).min(screen.height);
+    let area = Rect {
+        x: menu.position.x.min(screen.right().saturating_sub(width)),
+        y: (menu.position.y + 1).min(screen.bottom().saturating_sub(height)),
+        width,
+        height,
+    };
+    let items: Vec<ListItem> = ContextMenu::ACTIONS
+        .iter()
+        .map(|action| ListItem::new(action.description()))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(app.theme.border),
+        )
+        .style(app.theme.text)
+        .highlight_style(app.theme.selected);
+    frame.render_widget(Clear, area);
+    let mut state = ListState::default().with_selected(Some(menu.cursor));
+    StatefulWidget::render(list, area, frame.buffer_mut(), &mut state);
+    area
+}
+
+fn render_column_popup(frame: &mut Frame, app: &App, cursor: usize) {
+    let items: Vec<ListItem> = app
+        .columns
+        .order
+        .iter()
+        .map(|column| {
+            let mark = if app.columns.is_shown(*column) {
+                "x"
+            } else {
+                " "
+            };
+            ListItem::new(format!("[{}] {}", mark, column.label()))
+        })
+        .collect();
+    let area = centered(frame.area(), 34, items.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title("Columns")
+                .title_bottom(Line::from("space toggles, v closes").centered())
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(app.theme.border),
+        )
+        .style(app.theme.text)
+        .highlight_style(app.theme.selected);
+    frame.render_widget(Clear, area);
+    let mut state = ListState::default().with_selected(Some(cursor));
+    StatefulWidget::render(list, area, frame.buffer_mut(), &mut state);
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn suggestion_state(app: &mut App) -> ListState {
+    let last = app.suggestions.len().saturating_sub(1);
+    let selected = app.list_state.selected().map(|i| i.min(last));
+    app.list_state.select(selected);
+    ListState::default().with_selected(selected)
+}
+
+// scrolls the list so the selection stays within `height` lines and returns
+// the first visible row with a state relative to it
+fn list_window(app: &mut App, height: u16) -> (usize, ListState) {
+    let len = app.filtered.len();
+    let height = height as usize;
+    let selected = app
+        .list_state
+        .selected()
+        .map(|i| i.min(len.saturating_sub(1)));
+    let mut offset = app.list_state.offset().min(app.last_offset(height));
+    if let Some(i) = selected {
+        if i < offset {
+            offset = i;
+        }
+        while i >= offset + app.rows_fitting(offset, height) {
+            offset += 1;
+        }
+    }
+    app.list_state.select(selected.filter(|_| len > 0));
+    *app.list_state.offset_mut() = offset;
+    let window = ListState::default().with_selected(app.list_state.selected().map(|i| i - offset));
+    (offset, window)
+}
+
+// splits `name` into runs of matched and unmatched characters
+fn highlight_matches<'a>(name: &'a str, positions: &[usize], style: Style) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in name.chars().enumerate() {
+        let matched = positions.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            spans.push(match_span(std::mem::take(&mut run), run_matched, style));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(match_span(run, run_matched, style));
+    }
+    spans
+}
+
+fn match_span<'a>(text: String, matched: bool, style: Style) -> Span<'a> {
+    match matched {
+        true => Span::styled(text, style),
+        false => Span::raw(text),
+    }
+}
+
+// label and value rows of the info tab, the ones a package has no value for are left out
+fn info_rows(pak: &Package) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("name", pak.name.clone()),
+        ("version", pak.version.clone()),
+        ("repo", pak.repo.name().to_string()),
+        ("description", pak.descipt.clone()),
+    ];
+    if !pak.provides.is_empty() {
+        rows.push(("provides", pak.provides.join("  ")));
+    }
+    let installed = match (&pak.installed_version, pak.is_installed) {
+        (Some(version), _) if pak.upgradable => format!("{} (upgradable)", version),
+        (Some(version), _) => version.clone(),
+        (None, true) => "yes".to_string(),
+        (None, false) => "no".to_string(),
+    };
+    rows.push(("installed", installed));
+    if pak.is_installed {
+        let reason = match (pak.explicit, pak.orphan) {
+            (true, _) => "explicitly installed",
+            (false, true) => "dependency, orphaned",
+            (false, false) => "dependency",
+        };
+        rows.push(("reason", reason.to_string()));
+    }
+    if let ItemRepo::AUR(votes) = pak.repo {
+        rows.push(("votes", votes.to_string()));
+    }
+    if let Some(popularity) = pak.popularity {
+        rows.push(("popularity", format!("{:.2}", popularity)));
+    }
+    if pak.size > 0 {
+        rows.push(("download size", format_size(pak.size)));
+    }
+    if pak.installed_size > 0 {
+        rows.push(("installed size", format_size(pak.installed_size)));
+    }
+    let dates = [
+        ("build date", pak.build_date),
+        ("install date", pak.install_date),
+        ("last modified", pak.last_modified),
+    ];
+    rows.extend(
+        dates
+            .into_iter()
+            .filter_map(|(label, date)| Some((label, format_date(date?)))),
+    );
+    if let Some(repo) = &pak.shadows {
+        rows.push((
+            "note",
+            format!("shares its name with a package in {}", repo),
+        ));
+    }
+    rows
+}
+
+fn format_size(kib: u64) -> String {
+    match kib {
+        0..1024 => format!("{} KiB", kib),
+        1024..1048576 => format!("{:.1} MiB", kib as f64 / 1024.0),
+        _ => format!("{:.2} GiB", kib as f64 / 1048576.0),
+    }
+}
+
+// seconds since the epoch as a utc yyyy-mm-dd
+fn format_date(secs: i64) -> String {
+    let days = secs.div_euclid(86400);
+    // civil_from_days by Howard Hinnant
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// where tab_titles puts each title on the top border of the info block
+fn tab_areas(block: Rect) -> Vec<(InfoTab, Rect)> {
+    let mut x = block.x + 1;
+    InfoTab::ALL
+        .into_iter()
+        .map(|tab| {
+            let width = tab.title().chars().count() as u16 + 2;
+            let area = Rect::new(x, block.y, width, 1).intersection(block);
+            x += width;
+            (tab, area)
+        })
+        .collect()
+}
+
+fn tab_titles(active: InfoTab, theme: &Theme) -> Line<'static> {
+    let mut spans = Vec::new();
+    for tab in InfoTab::ALL {
+        let title = format!(" {} ", tab.title());
+        spans.push(match tab == active {
+            true => Span::styled(title, theme.selected),
+            false => Span::styled(title, theme.border),
+        });
+    }
+    Line::from(spans)
+}
+
 impl App {
     pub fn handle_key(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
         }
 
-        if !self.insert_mode.enabled {
+        if self.dialog.is_some() {
+            self.handle_dialog_key(key);
+            return;
+        }
+
+        if self.help.is_some() {
+            self.handle_help_key(key);
+            return;
+        }
+
+        if let Some(menu) = &mut self.context_menu {
+            let last = ContextMenu::ACTIONS.len() - 1;
             match key.code {
-                KeyCode::Esc | KeyCode::Char('q') => self.exit = true,
-                KeyCode::Char('h') | KeyCode::Left => self.select_none(),
-                KeyCode::Char('l') | KeyCode::Enter => {
-                    if let Some(i) = self.list_state.selected() {
-                        self.install_pack(i);
-                        println!("will went back to pacseen after type <enter>");
-                        io::stdin()
-                            .read_line(&mut String::new())
-                            .unwrap_or_default();
-
-                        let mut terminal: DefaultTerminal = ratatui::init();
-                        terminal.clear().unwrap_or_default();
-                        terminal.draw(|frame| {
-                            render(frame, self);
-                        });
-                    }
+                KeyCode::Esc | KeyCode::Char('q') => self.context_menu = None,
+                KeyCode::Char('j') | KeyCode::Down => menu.cursor = (menu.cursor + 1).min(last),
+                KeyCode::Char('k') | KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+                KeyCode::Enter | KeyCode::Char('l') => {
+                    let action = menu.action();
+                    self.context_menu = None;
+                    self.run_action(action);
+                    self.load_info_tab();
                 }
-                KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
-                KeyCode::Char('j') | KeyCode::Down => self.select_next(),
-                KeyCode::Char('g') | KeyCode::Home => self.select_first(),
-                KeyCode::Char('G') | KeyCode::End => self.select_last(),
-                KeyCode::Char('i') | KeyCode::Tab => self.insert_mode.enabled = true,
                 _ => {}
             }
-        } else {
+            return;
+        }
+
+        if let Some(cursor) = self.filter_popup {
+            let last = self.filter_options().len().saturating_sub(1);
             match key.code {
-                KeyCode::Backspace => self.delete_char(),
-                KeyCode::Enter | KeyCode::Tab => self.insert_mode.enabled = false,
-                KeyCode::Char(c) => self.enter_char(c),
-                KeyCode::Left => self.move_cursor_left(),
-                KeyCode::Right => self.move_cursor_right(),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('f') => self.filter_popup = None,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.filter_popup = Some((cursor + 1).min(last))
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.filter_popup = Some(cursor.saturating_sub(1))
+                }
+                KeyCode::Char(' ') | KeyCode::Enter => self.toggle_filter(cursor),
+                _ => {}
+            }
+            return;
+        }
 
+        if self.find.typing {
+            self.handle_find_key(key);
+            return;
+        }
+
+        if let Some(cursor) = self.column_popup {
+            let last = self.columns.order.len() - 1;
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('v') => self.column_popup = None,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.column_popup = Some((cursor + 1).min(last))
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.column_popup = Some(cursor.saturating_sub(1))
+                }
+                KeyCode::Char(' ') | KeyCode::Enter => {
+                    self.columns.toggle(self.columns.order[cursor])
+                }
                 _ => {}
             }
+            return;
+        }
 
+        if !self.insert_mode.enabled {
+            for result in self.keymap.press(KeyMode::Normal, KeyChord::from(key)) {
+                if let KeyResult::Action(action) = result {
+                    let count = self.keymap.take_count();
+                    self.run_counted(action, count);
+                }
+            }
+            self.load_info_tab();
+        } else if self.history.search.is_some() {
+            self.handle_history_search(key);
+            self.update_filter_local();
+        } else {
+            for result in self.keymap.press(KeyMode::Insert, KeyChord::from(key)) {
+                match result {
+                    KeyResult::Action(action) => self.run_action(action),
+                    KeyResult::Pending => return,
+                    KeyResult::Unbound(key) => {
+                        if let Some(c) = key.typed() {
+                            self.enter_char(c);
+                        }
+                    }
+                }
+            }
             self.update_filter_local();
         }
     }
 
+    pub fn run_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.exit = true,
+            Action::SelectNext => self.select_next(),
+            Action::SelectPrevious => self.select_previous(),
+            Action::SelectFirst => self.select_first(),
+            Action::SelectLast => self.select_last(),
+            Action::SelectNone => self.select_none(),
+            Action::HalfPageDown => self.scroll_page((self.list_height() / 2).max(1) as isize),
+            Action::HalfPageUp => self.scroll_page(-((self.list_height() / 2).max(1) as isize)),
+            Action::PageDown => self.scroll_page(self.list_height() as isize),
+            Action::PageUp => self.scroll_page(-(self.list_height() as isize)),
+            Action::ViewTop => self.view_row(0, false),
+            Action::ViewMiddle => self.view_middle(),
+            Action::ViewBottom => self.view_row(0, true),
+            Action::CenterView => self.center_view(),
+            Action::Install if self.filtered.is_empty() => {
+                if let Some(i) = self.list_state.selected() {
+                    self.accept_suggestion(i);
+                }
+            }
+            Action::Install => {
+                let Some(i) = self
+                    .list_state
+                    .selected()
+                    .filter(|i| *i < self.filtered.len())
+                else {
+                    return;
+                };
+                let pack = self.package(self.filtered[i].source);
+                if pack.is_installed {
+                    self.confirm_remove(pack.name.clone());
+                    return;
+                }
+                self.install_pack(i);
+                self.resume_after_helper();
+            }
+            Action::Mark => self.toggle_mark(),
+            Action::Batch => self.open_batch(),
+            Action::CleanCache => self.open_cache_cleanup(),
+            Action::EnterInsert => self.insert_mode.enabled = true,
+            Action::NextTab => self.switch_tab(self.info_tab.next()),
+            Action::PreviousTab => self.switch_tab(self.info_tab.previous()),
+            Action::ScrollDown => self.scroll_info(true),
+            Action::ScrollUp => self.scroll_info(false),
+            Action::ToggleSmartCase => self.toggle_smart_case(),
+            Action::ToggleRegex => self.toggle_regex_mode(),
+            Action::ToggleFullText => self.toggle_fulltext_mode(),
+            Action::Find => self.start_find(),
+            Action::FindNext => self.find_next(true),
+            Action::FindPrevious => self.find_next(false),
+            Action::OpenFilters => self.filter_popup = Some(0),
+            Action::OpenColumns => self.column_popup = Some(0),
+            Action::GrowList => self.resize_list(1),
+            Action::ShrinkList => self.resize_list(-1),
+            Action::ResetLayout => self.reset_layout(),
+            Action::MaximizeList => self.toggle_maximized(Pane::List),
+            Action::MaximizeInfo => self.toggle_maximized(Pane::Info),
+            Action::CycleSort => self.cycle_sort(),
+            Action::ToggleSortDirection => self.toggle_sort_direction(),
+            Action::CheckUpdates => {
+                self.check_updates();
+                self.switch_tab(InfoTab::Updates);
+            }
+            Action::UpgradeAur if self.info_tab == InfoTab::Updates => {
+                if self.upgrade_aur_packages() {
+                    self.resume_after_helper();
+                }
+            }
+            Action::UpgradeAur => {}
+            Action::Help => {
+                let mode = match self.insert_mode.enabled {
+                    true => KeyMode::Insert,
+                    false => KeyMode::Normal,
+                };
+                self.help = Some(Help::new(mode));
+            }
+            Action::SubmitSearch => {
+                self.submit_search();
+                self.insert_mode.enabled = false
+            }
+            Action::HistorySearch => self.history.start_search(&self.search),
+            Action::HistoryOlder => {
+                if let Some(query) = self.history.older(&self.search).map(str::to_string) {
+                    self.set_search(query);
+                }
+            }
+            Action::HistoryNewer => {
+                if let Some(query) = self.history.newer().map(str::to_string) {
+                    self.set_search(query);
+                }
+            }
+            Action::CursorLeft => self.move_cursor_left(),
+            Action::CursorRight => self.move_cursor_right(),
+            Action::DeleteBackward => self.delete_char(),
+            Action::DeleteForward => self.delete_forward(),
+            Action::DeleteWordBackward => self.kill(
+                rubout_start(&self.search, self.insert_mode.index),
+                self.insert_mode.index,
+            ),
+            Action::KillToStart => self.kill(0, self.insert_mode.index),
+            Action::KillToEnd => self.kill(self.insert_mode.index, self.search.chars().count()),
+            Action::Yank => self.yank(),
+            Action::Undo => self.undo(),
+            Action::CursorStart => self.set_cursor(0),
+            Action::CursorEnd => self.set_cursor(self.search.chars().count()),
+            Action::WordLeft => self.set_cursor(word_left(&self.search, self.insert_mode.index)),
+            Action::WordRight => self.set_cursor(word_right(&self.search, self.insert_mode.index)),
+            Action::Unbound => {}
+        }
+    }
+
+    // the search box previews the match, Enter keeps it for editing and Esc
+    // brings back what was typed before
+    fn handle_history_search(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('r') if ctrl => self.history.search_older(),
+            KeyCode::Char('g') if ctrl => {
+                let draft = self.history.end_search(false);
+                self.set_search(draft);
+                return;
+            }
+            KeyCode::Esc => {
+                let draft = self.history.end_search(false);
+                self.set_search(draft);
+                return;
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                let found = self.history.end_search(true);
+                self.set_search(found);
+                return;
+            }
+            KeyCode::Backspace => self.history.search_pop(),
+            KeyCode::Char(c) if !ctrl => self.history.search_push(c),
+            _ => {}
+        }
+        let preview = self.history.search_preview().to_string();
+        self.set_search(preview);
+    }
+
+    // the overlay has its own fixed keys, `/` starts typing a search that
+    // Enter keeps and Esc drops
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let len = help.lines(&self.keymap).len();
+        if help.searching {
+            match key.code {
+                KeyCode::Esc => {
+                    help.query.clear();
+                    help.searching = false;
+                }
+                KeyCode::Enter => help.searching = false,
+                KeyCode::Backspace => {
+                    help.query.pop();
+                }
+                KeyCode::Char(c) if !ctrl => {
+                    help.query.push(c);
+                    help.scroll = 0;
+                }
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::F(1) => {
+                self.help = None
+            }
+            KeyCode::Char('/') => help.searching = true,
+            KeyCode::Char('j') | KeyCode::Down => help.scroll_by(1, len),
+            KeyCode::Char('k') | KeyCode::Up => help.scroll_by(-1, len),
+            KeyCode::Char('d') if ctrl => help.scroll_by(10, len),
+            KeyCode::Char('u') if ctrl => help.scroll_by(-10, len),
+            KeyCode::PageDown => help.scroll_by(10, len),
+            KeyCode::PageUp => help.scroll_by(-10, len),
+            KeyCode::Char('g') | KeyCode::Home => help.scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => help.scroll_by(isize::MAX, len),
+            _ => {}
+        }
+    }
+
+    // takes the terminal back from a helper, the terminal main set up stays
+    // in use and is cleared before its next frame
+    fn resume_after_helper(&mut self) {
+        wait_for_enter();
+        if let Err(e) = enter_tui() {
+            self.notice = Notice::error(format!("failed to restore the terminal: {}", e));
+        }
+        set_mouse_capture(self.config.mouse);
+        set_bracketed_paste(true);
+        self.redraw = true;
+    }
+
+    fn switch_tab(&mut self, tab: InfoTab) {
+        self.info_tab = tab;
+    }
+
     fn select_none(&mut self) {
         self.list_state.select(None);
     }
@@ -186,29 +896,17 @@ impl App {
     }
 
     pub fn render_selected_item(&self, area: Rect, buf: &mut Buffer) {
-        let info = if let Some(i) = self.list_state.selected() {
-            let pak = self.filtered[i].clone();
-            let repo_name = match &pak.repo {
-                ItemRepo::Core => "core",
-                ItemRepo::Extra => "Extra",
-                ItemRepo::Archlinuxcn => "archlinuxcn",
-                ItemRepo::Multilib => "multilib",
-                ItemRepo::AUR(_) => "aur",
-                ItemRepo::AbsOther(string) => &string.clone()[..],
-            };
-            match pak.repo {
-                ItemRepo::AUR(votes) => format!(
-                    "name:\t{}\nrepo:\t{}\ninstalled:\t{}\ndesc:\t{}\nvote:\t{}\n",
-                    pak.name, repo_name, pak.is_installed, pak.descipt, votes,
-                ),
-
-                _ => format!(
-                    "name:\t{}\nrepo:\t{}\ninstalled:\t{}\ndesc:\t{}\n",
-                    pak.name, repo_name, pak.is_installed, pak.descipt
-                ),
-            }
-        } else {
-            "Nothing selected".to_string()
+        let lines = match self.selected_package() {
+            Some(pak) => info_rows(pak)
+                .into_iter()
+                .map(|(label, value)| {
+                    Line::from(vec![
+                        Span::raw(format!("{:<14}", label)).style(self.theme.label),
+                        Span::raw(value),
+                    ])
+                })
+                .collect(),
+            None => vec![Line::raw("Nothing selected")],
         };
 
         let block = Block::new()
@@ -216,51 +914,306 @@ impl App {
             .borders(Borders::TOP)
             .padding(Padding::horizontal(1));
 
-        Paragraph::new(info)
+        Paragraph::new(lines)
             .block(block)
-            .fg(TEXT_FG_COLOR)
+            .style(self.theme.text)
             .wrap(Wrap { trim: false })
+            .scroll((self.info_scroll(InfoTab::Info), 0))
             .render(area, buf);
     }
-    fn delete_char(&mut self) {
-        let is_not_cursor_left_most = self.insert_mode.index != 0;
-        if is_not_cursor_left_most {
-            let current_index = self.insert_mode.index;
-            let from_left_to_current_index = current_index - 1;
-
-            let before_char_to_delete = self.search.chars().take(from_left_to_current_index);
-            let after_char_to_delete = self.search.chars().skip(current_index);
 
-            self.search = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+    // the loaded data when it belongs to the selection, otherwise a line
+    // saying why there is none
+    fn tab_placeholder(
+        &self,
+        tab: InfoTab,
+        loaded: Option<&str>,
+        task: &Option<JoinHandle<()>>,
+    ) -> Option<Line<'static>> {
+        let pak = match self.selected_package() {
+            Some(pak) if loaded != Some(pak.name.as_str()) => pak,
+            Some(_) => return None,
+            None => return Some(Line::raw("Nothing selected")),
+        };
+        if let Some(e) = self.load_error(tab, &pak.name) {
+            return Some(
+                Line::raw(format!("failed to load {}: {}", pak.name, e))
+                    .style(self.theme.notice(NoticeLevel::Error)),
+            );
+        }
+        match task.as_ref().is_some_and(|t| !t.is_finished()) {
+            true => Some(Line::raw(format!("loading {}...", pak.name))),
+            false => {
+                Some(Line::raw(format!("nothing loaded for {}", pak.name)).style(self.theme.dim))
+            }
         }
     }
 
-    fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.insert_mode.index.saturating_sub(1);
-        self.insert_mode.index = self.clamp_cursor(cursor_moved_left);
+    pub fn render_package_deps(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("Dependencies").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let loaded = self.package_deps.as_ref().map(|d| d.name.as_str());
+        let lines = match (
+            self.tab_placeholder(InfoTab::Dependencies, loaded, &self.deps_task),
+            &self.package_deps,
+        ) {
+            (Some(line), _) => vec![line],
+            (None, None) => Vec::new(),
+            (None, Some(deps)) if deps.sections().is_empty() => {
+                vec![Line::raw("no dependencies")]
+            }
+            (None, Some(deps)) => {
+                let mut lines = Vec::new();
+                for (title, list) in deps.sections() {
+                    lines.push(
+                        Line::raw(format!("{} ({})", title, list.len())).style(self.theme.label),
+                    );
+                    lines.extend(list.iter().map(|dep| Line::raw(format!("  {}", dep))));
+                    lines.push(Line::raw(""));
+                }
+                lines
+            }
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .scroll((self.info_scroll(InfoTab::Dependencies), 0))
+            .render(area, buf);
+    }
+
+    pub fn render_package_files(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("Files").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let loaded = self.package_files.as_ref().map(|f| f.name.as_str());
+        let lines = match (
+            self.tab_placeholder(InfoTab::Files, loaded, &self.files_task),
+            &self.package_files,
+        ) {
+            (Some(line), _) => vec![line],
+            (None, None) => Vec::new(),
+            (None, Some(files)) => {
+                let from = if files.installed {
+                    "installed"
+                } else {
+                    "files db"
+                };
+                let mut lines = vec![
+                    Line::raw(format!("{} files ({})", files.files.len(), from))
+                        .style(self.theme.label),
+                ];
+                lines.extend(files.files.iter().map(|f| match f.ends_with('/') {
+                    true => Line::raw(f.clone()).style(self.theme.dim),
+                    false => Line::raw(f.clone()),
+                }));
+                lines
+            }
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .scroll((self.info_scroll(InfoTab::Files), 0))
+            .render(area, buf);
     }
 
-    fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.insert_mode.index.saturating_add(1);
-        self.insert_mode.index = self.clamp_cursor(cursor_moved_right);
+    pub fn render_package_history(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("History").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let loaded = self.package_history.as_ref().map(|h| h.name.as_str());
+        let lines = match (
+            self.tab_placeholder(InfoTab::History, loaded, &self.history_task),
+            &self.package_history,
+        ) {
+            (Some(line), _) => vec![line],
+            (None, None) => Vec::new(),
+            (None, Some(history)) if history.entries.is_empty() => {
+                vec![Line::raw("pacman.log has no record of it")]
+            }
+            // newest first
+            (None, Some(history)) => history
+                .entries
+                .iter()
+                .rev()
+                .map(|entry| {
+                    let action = Span::raw(format!("{:<12}", entry.action));
+                    Line::from(vec![
+                        Span::raw(format!("{}  ", entry.date)).style(self.theme.dim),
+                        match entry.action.as_str() {
+                            "removed" => action.style(self.theme.removed),
+                            "installed" => action.style(self.theme.added),
+                            _ => action,
+                        },
+                        Span::raw(entry.version.clone()),
+                    ])
+                })
+                .collect(),
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .scroll((self.info_scroll(InfoTab::History), 0))
+            .render(area, buf);
     }
 
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.search.chars().count())
+    pub fn render_aur_source(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("PKGBUILD").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let lines = match (self.selected_package(), &self.aur_source) {
+            (None, _) => vec![Line::raw("Nothing selected")],
+            (Some(pak), _) if !matches!(pak.repo, ItemRepo::AUR(_)) => {
+                vec![Line::raw("PKGBUILDs are only shown for aur packages")]
+            }
+            (Some(pak), Some(source)) if source.name == pak.name => {
+                let mut lines = vec![
+                    Line::from(vec![
+                        Span::raw("files: ").style(self.theme.label),
+                        Span::raw(source.files.join("  ")),
+                    ]),
+                    Line::raw(format!("cache: {}", source.dir.display())).style(self.theme.dim),
+                    Line::raw(""),
+                ];
+                lines.extend(highlight_shell(&source.pkgbuild, &self.theme.syntax));
+                lines
+            }
+            (Some(pak), _) => match self.load_error(InfoTab::Pkgbuild, &pak.name) {
+                Some(e) => vec![
+                    Line::raw(format!("failed to fetch {}: {}", pak.name, e))
+                        .style(self.theme.notice(NoticeLevel::Error)),
+                ],
+                None => vec![Line::raw(format!("fetching {}...", pak.name))],
+            },
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .scroll((self.info_scroll(self.info_tab), 0))
+            .render(area, buf);
     }
 
-    fn enter_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        self.search.insert(index, new_char);
-        self.move_cursor_right();
+    pub fn render_aur_comments(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("Comments").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let lines = match (self.selected_package(), &self.aur_comments) {
+            (None, _) => vec![Line::raw("Nothing selected")],
+            (Some(pak), _) if !matches!(pak.repo, ItemRepo::AUR(_)) => {
+                vec![Line::raw("comments are only shown for aur packages")]
+            }
+            (Some(pak), Some(c)) if c.name == pak.name && c.comments.is_empty() => {
+                vec![Line::raw("no comments yet")]
+            }
+            (Some(pak), Some(c)) if c.name == pak.name => {
+                let mut lines = Vec::new();
+                for comment in &c.comments {
+                    let mut header = Vec::new();
+                    if comment.pinned {
+                        header.push(Span::raw("[pinned] ").style(self.theme.marked));
+                    }
+                    header.push(Span::raw(comment.author.clone()).style(self.theme.label));
+                    header.push(Span::raw(format!(" {}", comment.date)).style(self.theme.dim));
+                    if let Some(edited) = &comment.edited {
+                        header.push(
+                            Span::raw(format!(" ({})", edited))
+                                .style(self.theme.dim)
+                                .italic(),
+                        );
+                    }
+                    lines.push(Line::from(header));
+                    lines.extend(comment.content.lines().map(|l| Line::raw(l.to_string())));
+                    lines.push(Line::raw(""));
+                }
+                lines
+            }
+            (Some(pak), _) => match self.load_error(InfoTab::Comments, &pak.name) {
+                Some(e) => vec![
+                    Line::raw(format!("failed to fetch comments of {}: {}", pak.name, e))
+                        .style(self.theme.notice(NoticeLevel::Error)),
+                ],
+                None => vec![Line::raw(format!("fetching comments of {}...", pak.name))],
+            },
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.info_scroll(self.info_tab), 0))
+            .render(area, buf);
     }
 
-    fn byte_index(&self) -> usize {
-        self.search
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.insert_mode.index)
-            .unwrap_or(self.search.len())
+    pub fn render_aur_updates(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .title(Line::raw("AUR Updates").centered())
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1));
+
+        let lines = match &self.aur_updates {
+            None if self.updates_task.as_ref().is_some_and(|t| !t.is_finished()) => {
+                vec![Line::raw("checking...")]
+            }
+            None => vec![Line::raw(
+                "press 'u' to check the installed aur packages for updates",
+            )],
+            Some(report) => {
+                let mut lines = vec![
+                    Line::raw(format!(
+                        "updates ({}), press 'U' to upgrade them",
+                        report.updates.len()
+                    ))
+                    .style(self.theme.label),
+                ];
+                for update in &report.updates {
+                    lines.push(Line::from(vec![
+                        Span::raw(format!("  {} ", update.name)),
+                        Span::raw(update.local_version.clone()).style(self.theme.removed),
+                        Span::raw(" -> "),
+                        Span::raw(update.aur_version.clone()).style(self.theme.added),
+                    ]));
+                }
+                lines.push(Line::raw(""));
+                lines.push(
+                    Line::raw(format!("flagged out-of-date ({})", report.flagged.len()))
+                        .style(self.theme.label),
+                );
+                for flagged in &report.flagged {
+                    lines.push(
+                        Line::raw(format!("  {} {}", flagged.name, flagged.aur_version))
+                            .style(self.theme.flagged),
+                    );
+                }
+                lines.push(Line::raw(""));
+                lines.push(
+                    Line::raw(format!("not in the aur ({})", report.missing.len()))
+                        .style(self.theme.label),
+                );
+                for name in &report.missing {
+                    lines.push(Line::raw(format!("  {}", name)).style(self.theme.dim));
+                }
+                lines
+            }
+        };
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(self.theme.text)
+            .scroll((self.info_scroll(self.info_tab), 0))
+            .render(area, buf);
     }
 }
@@ -0,0 +1,137 @@
+use std::{io, time::Instant};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+};
+use ratatui::layout::Position;
+
+use crate::objects::{
+    keymap::Action,
+    mouse::{Click, ContextMenu, DOUBLE_CLICK, WHEEL_LINES},
+    stat::App,
+};
+
+// turned off around paru so its prompts get plain input
+pub fn set_mouse_capture(enabled: bool) {
+    let _ = match enabled {
+        true => execute!(io::stdout(), EnableMouseCapture),
+        false => execute!(io::stdout(), DisableMouseCapture),
+    };
+}
+
+impl App {
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let pos = Position::new(mouse.column, mouse.row);
+        if self.dialog.is_some() {
+            return;
+        }
+        if let Some(help) = &mut self.help {
+            let len = help.lines(&self.keymap).len();
+            match mouse.kind {
+                MouseEventKind::ScrollDown => help.scroll_by(WHEEL_LINES as isize, len),
+                MouseEventKind::ScrollUp => help.scroll_by(-(WHEEL_LINES as isize), len),
+                _ => {}
+            }
+            return;
+        }
+        if self.filter_popup.is_some() || self.column_popup.is_some() {
+            return;
+        }
+        if self.context_menu.is_some() {
+            self.handle_menu_mouse(mouse.kind, pos);
+            return;
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.scroll_at(pos, true),
+            MouseEventKind::ScrollUp => self.scroll_at(pos, false),
+            MouseEventKind::Down(MouseButton::Left) => self.click(pos),
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(row) = self.areas.row_at(pos) {
+                    self.list_state.select(Some(row));
+                    self.context_menu = Some(ContextMenu::new(pos));
+                }
+            }
+            _ => {}
+        }
+        self.load_info_tab();
+    }
+
+    // the menu swallows the mouse until a click picks an entry or lands
+    // outside of it
+    fn handle_menu_mouse(&mut self, kind: MouseEventKind, pos: Position) {
+        let area = self.areas.menu;
+        let entry = (area.contains(pos) && pos.y > area.y)
+            .then(|| (pos.y - area.y - 1) as usize)
+            .filter(|i| *i < ContextMenu::ACTIONS.len());
+        match kind {
+            MouseEventKind::Moved => {
+                if let (Some(menu), Some(i)) = (&mut self.context_menu, entry) {
+                    menu.cursor = i;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.context_menu = None;
+                if let Some(i) = entry {
+                    self.run_action(ContextMenu::ACTIONS[i]);
+                    self.load_info_tab();
+                }
+            }
+            MouseEventKind::Down(_) => self.context_menu = None,
+            _ => {}
+        }
+    }
+
+    // the wheel moves the selection over the list and scrolls the info pane
+    fn scroll_at(&mut self, pos: Position, down: bool) {
+        if self.areas.info.contains(pos) {
+            for _ in 0..WHEEL_LINES {
+                self.scroll_info(down);
+            }
+            return;
+        }
+        if !self.areas.list.contains(pos) {
+            return;
+        }
+        let len = match self.filtered.is_empty() {
+            true => self.suggestions.len(),
+            false => self.filtered.len(),
+        };
+        if len == 0 {
+            return;
+        }
+        let selected = match (self.list_state.selected(), down) {
+            (None, _) => 0,
+            (Some(i), true) => (i + WHEEL_LINES).min(len - 1),
+            (Some(i), false) => i.saturating_sub(WHEEL_LINES),
+        };
+        self.list_state.select(Some(selected));
+    }
+
+    // a click selects, a second one on the same row runs the default action
+    fn click(&mut self, pos: Position) {
+        if self.areas.search.contains(pos) {
+            self.insert_mode.enabled = true;
+            return;
+        }
+        if let Some(tab) = self.areas.tab_at(pos) {
+            self.switch_tab(tab);
+            return;
+        }
+        let Some(row) = self.areas.row_at(pos) else {
+            return;
+        };
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|c| c.row == row && now.duration_since(c.at) <= DOUBLE_CLICK);
+        self.list_state.select(Some(row));
+        match double {
+            true => {
+                self.last_click = None;
+                self.run_action(Action::Install);
+            }
+            false => self.last_click = Some(Click { at: now, row }),
+        }
+    }
+}
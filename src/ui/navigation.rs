@@ -0,0 +1,228 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    objects::{
+        find::find_row,
+        keymap::Action,
+        stat::{App, Notice},
+    },
+    ui::table::section_header,
+};
+
+impl App {
+    // a count repeats a motion, for gg and G it is the row to go to and for
+    // H and L the row from the top or bottom of the view
+    pub fn run_counted(&mut self, action: Action, count: Option<usize>) {
+        let Some(n) = count.filter(|n| *n > 0) else {
+            self.run_action(action);
+            return;
+        };
+        match action {
+            Action::SelectNext => self.move_selection(n as isize),
+            Action::SelectPrevious => self.move_selection(-(n as isize)),
+            Action::SelectFirst | Action::SelectLast => self.select_row(n - 1),
+            Action::ViewTop => self.view_row(n - 1, false),
+            Action::ViewBottom => self.view_row(n - 1, true),
+            Action::GrowList => self.resize_list(n as i32),
+            Action::ShrinkList => self.resize_list(-(n as i32)),
+            Action::HalfPageDown
+            | Action::HalfPageUp
+            | Action::PageDown
+            | Action::PageUp
+            | Action::ScrollDown
+            | Action::ScrollUp
+            | Action::NextTab
+            | Action::PreviousTab
+            | Action::FindNext
+            | Action::FindPrevious => {
+                for _ in 0..n {
+                    self.run_action(action);
+                }
+            }
+            _ => self.run_action(action),
+        }
+    }
+
+    // the rows of the list, the suggestions when they stand in for it
+    fn row_count(&self) -> usize {
+        match self.filtered.is_empty() {
+            true => self.suggestions.len(),
+            false => self.filtered.len(),
+        }
+    }
+
+    // lines of row `i`, a row under a section heading takes two
+    fn row_lines(&self, i: usize) -> usize {
+        match !self.filtered.is_empty() && section_header(self, i).is_some() {
+            true => 2,
+            false => 1,
+        }
+    }
+
+    // how many rows from `offset` on fit in `lines`, at least one
+    pub fn rows_fitting(&self, offset: usize, lines: usize) -> usize {
+        let mut used = 0;
+        let rows = (offset..self.row_count())
+            .take_while(|i| {
+                used += self.row_lines(*i);
+                used <= lines
+            })
+            .count();
+        rows.max(1)
+    }
+
+    // the lowest the view goes, with the last row at the bottom of `lines`
+    pub fn last_offset(&self, lines: usize) -> usize {
+        let len = self.row_count();
+        let mut used = 0;
+        let rows = (0..len)
+            .rev()
+            .take_while(|i| {
+                used += self.row_lines(*i);
+                used <= lines
+            })
+            .count();
+        len - rows.max(1).min(len)
+    }
+
+    // the rows the list shows from its current offset, as tall as it was
+    // last drawn
+    pub fn list_height(&self) -> usize {
+        self.rows_fitting(self.list_state.offset(), self.areas.list.height as usize)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let Some(last) = self.row_count().checked_sub(1) else {
+            return;
+        };
+        let row = match self.list_state.selected() {
+            Some(i) => (i.min(last) as isize + delta).clamp(0, last as isize) as usize,
+            None => 0,
+        };
+        self.list_state.select(Some(row));
+    }
+
+    pub fn select_row(&mut self, row: usize) {
+        if let Some(last) = self.row_count().checked_sub(1) {
+            self.list_state.select(Some(row.min(last)));
+        }
+    }
+
+    // moves the view along with the selection, so it stays on the same line
+    pub fn scroll_page(&mut self, delta: isize) {
+        let top = self.last_offset(self.areas.list.height as usize);
+        let offset = (self.list_state.offset() as isize + delta).clamp(0, top as isize);
+        *self.list_state.offset_mut() = offset as usize;
+        self.move_selection(delta);
+    }
+
+    // `n` rows from the top of the view, or from its bottom
+    pub fn view_row(&mut self, n: usize, from_bottom: bool) {
+        let offset = self.list_state.offset();
+        let visible = self
+            .row_count()
+            .saturating_sub(offset)
+            .min(self.list_height());
+        let Some(last) = visible.checked_sub(1) else {
+            return;
+        };
+        let row = match from_bottom {
+            true => last.saturating_sub(n),
+            false => n.min(last),
+        };
+        self.list_state.select(Some(offset + row));
+    }
+
+    pub fn view_middle(&mut self) {
+        let offset = self.list_state.offset();
+        let visible = self
+            .row_count()
+            .saturating_sub(offset)
+            .min(self.list_height());
+        self.view_row(visible.saturating_sub(1) / 2, false);
+    }
+
+    pub fn center_view(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            *self.list_state.offset_mut() = i.saturating_sub(self.list_height() / 2);
+        }
+    }
+
+    pub fn start_find(&mut self) {
+        self.find.pattern.clear();
+        self.find.typing = true;
+        self.find.origin = self.list_state.selected();
+    }
+
+    // typing moves to the first match after where the search started, Enter
+    // keeps it and Esc goes back
+    pub fn handle_find_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.find.typing = false;
+                self.find.pattern.clear();
+                self.list_state.select(self.find.origin);
+                return;
+            }
+            KeyCode::Enter => {
+                self.find.typing = false;
+                if self.find_from(self.find.origin, true).is_none() && !self.find.pattern.is_empty()
+                {
+                    self.notice = Notice::warning(format!("{} not found", self.find.pattern));
+                }
+                return;
+            }
+            KeyCode::Backspace if self.find.pattern.is_empty() => {
+                self.find.typing = false;
+                return;
+            }
+            KeyCode::Backspace => {
+                self.find.pattern.pop();
+            }
+            KeyCode::Char(c) if !ctrl => self.find.pattern.push(c),
+            _ => return,
+        }
+        self.follow_find();
+    }
+
+    // the selection goes to the first match of the pattern as typed so far
+    pub fn follow_find(&mut self) {
+        match self.find_from(self.find.origin, true) {
+            Some(row) => self.list_state.select(Some(row)),
+            None => self.list_state.select(self.find.origin),
+        }
+    }
+
+    // the row of the next match after `from`, the top of the list counts
+    // as coming after nothing
+    fn find_from(&self, from: Option<usize>, forward: bool) -> Option<usize> {
+        let names: Vec<&str> = self
+            .filtered
+            .iter()
+            .map(|hit| self.package(hit.source).name.as_str())
+            .collect();
+        let from = from.unwrap_or(names.len().saturating_sub(1));
+        find_row(&names, &self.find.pattern, from, forward)
+    }
+
+    pub fn find_next(&mut self, forward: bool) {
+        if self.find.pattern.is_empty() {
+            self.notice = Notice::warning("no previous find");
+            return;
+        }
+        let from = self.list_state.selected();
+        let Some(row) = self.find_from(from, forward) else {
+            self.notice = Notice::warning(format!("{} not found", self.find.pattern));
+            return;
+        };
+        let wrapped = from.is_some_and(|i| (row <= i) == forward);
+        if wrapped {
+            self.notice = Notice::info(match forward {
+                true => "find hit the bottom, continuing at the top",
+                false => "find hit the top, continuing at the bottom",
+            });
+        }
+        self.list_state.select(Some(row));
+    }
+}
@@ -0,0 +1,235 @@
+use ratatui::{
+    layout::Constraint,
+    text::{Line, Span, Text},
+    widgets::{Cell, Row},
+};
+
+use crate::{
+    objects::{
+        columns::Column,
+        fulltext::Field,
+        stat::{App, Hit, ItemRepo},
+    },
+    ui::{edit::text_width, format_date, format_size, highlight_matches},
+};
+
+const ELLIPSIS: &str = "…";
+const NAME_MIN_WIDTH: u16 = 8;
+
+// the widest a column gets, the name takes whatever is left
+fn max_width(column: Column) -> u16 {
+    match column {
+        Column::Installed => 2,
+        Column::Name => u16::MAX,
+        Column::Version | Column::InstalledVersion => 16,
+        Column::Repo => 14,
+        Column::Size => 10,
+        Column::Votes | Column::Popularity => 6,
+        Column::Updated => 10,
+    }
+}
+
+// the rows of the table from `offset` on, its header and column widths,
+// and the result on every line for mouse clicks
+pub struct PackageRows<'a> {
+    pub header: Row<'a>,
+    pub rows: Vec<Row<'a>>,
+    pub widths: Vec<Constraint>,
+    pub lines: Vec<Option<usize>>,
+}
+
+// the heading above result `i` when it starts the repo or the aur section,
+// such a row is two lines tall
+pub fn section_header(app: &App, i: usize) -> Option<&'static str> {
+    let is_aur = |i: usize| matches!(app.package(app.filtered[i].source).repo, ItemRepo::AUR(_));
+    (app.config.section_headers && (i == 0 || is_aur(i) != is_aur(i - 1)))
+        .then(|| if is_aur(i) { "AUR" } else { "Repositories" })
+}
+
+pub fn package_rows<'a>(app: &'a App, offset: usize, count: usize, width: u16) -> PackageRows<'a> {
+    let theme = &app.theme;
+    let columns = app.columns.shown();
+    let hits: Vec<(usize, &Hit)> = app
+        .filtered
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(count)
+        .collect();
+    let cells: Vec<Vec<Vec<Span>>> = hits
+        .iter()
+        .map(|(_, hit)| columns.iter().map(|c| cell(app, hit, *c)).collect())
+        .collect();
+
+    // every column as wide as its widest cell within its limit, the name gets
+    // the rest
+    let mut widths: Vec<u16> = columns
+        .iter()
+        .enumerate()
+        .map(|(n, column)| {
+            let widest = cells
+                .iter()
+                .map(|row| spans_width(&row[n]))
+                .max()
+                .unwrap_or(0);
+            widest
+                .max(text_width(column.title()))
+                .min(max_width(*column))
+        })
+        .collect();
+    let spacing = columns.len().saturating_sub(1) as u16;
+    let name = columns.iter().position(|c| *c == Column::Name);
+    if let Some(n) = name {
+        widths[n] = 0;
+    }
+    // a narrow list squeezes the widest columns first
+    let budget = width.saturating_sub(spacing + name.map_or(0, |_| NAME_MIN_WIDTH));
+    while widths.iter().sum::<u16>() > budget {
+        let Some(widest) = widths.iter_mut().max().filter(|w| **w > 1) else {
+            break;
+        };
+        *widest -= 1;
+    }
+    if let Some(n) = name {
+        widths[n] = width.saturating_sub(spacing + widths.iter().sum::<u16>());
+    }
+
+    let mut lines = Vec::new();
+    let rows = hits
+        .iter()
+        .zip(cells)
+        .map(|((i, _), row)| {
+            let header = section_header(app, *i);
+            // the heading goes above the name, or the first column without one
+            let heading_column = name.unwrap_or(0);
+            let cells = row.into_iter().enumerate().map(|(n, spans)| {
+                let mut line = truncate(spans, widths[n]);
+                if columns[n].numeric() {
+                    line = line.right_aligned();
+                }
+                let mut text = Text::default();
+                if let Some(header) = header {
+                    let heading = match n == heading_column {
+                        true => {
+                            truncate(vec![Span::styled(header, theme.section_header)], widths[n])
+                        }
+                        false => Line::default(),
+                    };
+                    text.lines.push(heading);
+                }
+                text.lines.push(line);
+                Cell::from(text)
+            });
+            if header.is_some() {
+                lines.push(None);
+            }
+            lines.push(Some(*i));
+            Row::new(cells).height(if header.is_some() { 2 } else { 1 })
+        })
+        .collect();
+
+    let header = Row::new(columns.iter().zip(&widths).map(|(column, width)| {
+        let line = truncate(vec![Span::raw(column.title())], *width);
+        match column.numeric() {
+            true => line.right_aligned(),
+            false => line,
+        }
+    }))
+    .style(theme.label);
+    PackageRows {
+        header,
+        rows,
+        widths: widths.into_iter().map(Constraint::Length).collect(),
+        lines,
+    }
+}
+
+fn cell<'a>(app: &'a App, hit: &'a Hit, column: Column) -> Vec<Span<'a>> {
+    let theme = &app.theme;
+    let p = app.package(hit.source);
+    let text = |text: String| vec![Span::raw(text)];
+    match column {
+        Column::Installed => vec![
+            Span::styled(
+                if app.marked.contains(&p.name) {
+                    "*"
+                } else {
+                    " "
+                },
+                theme.marked,
+            ),
+            Span::styled(if p.is_installed { "●" } else { " " }, theme.installed),
+        ],
+        Column::Name => {
+            let mut spans = highlight_matches(&p.name, &hit.positions, theme.matched);
+            // where a full-text search found the package, when not by name
+            if let Some(m) = hit.matched.as_ref().filter(|m| m.field != Field::Name) {
+                spans.push(Span::styled(
+                    format!("  {}: {}", m.field.label(), m.text),
+                    theme.text_match,
+                ));
+            }
+            spans
+        }
+        Column::Version => text(p.version.clone()),
+        Column::InstalledVersion => text(p.installed_version.clone().unwrap_or_default()),
+        Column::Repo => {
+            let mut spans = vec![Span::styled(p.repo.name(), theme.repo_style(p.repo.name()))];
+            if p.shadows.is_some() {
+                spans.push(Span::styled(" !", theme.shadowed));
+            }
+            spans
+        }
+        Column::Size if p.installed_size > 0 => text(format_size(p.installed_size)),
+        Column::Size => Vec::new(),
+        Column::Votes => match p.repo {
+            ItemRepo::AUR(votes) => text(votes.to_string()),
+            _ => Vec::new(),
+        },
+        Column::Popularity => text(
+            p.popularity
+                .map(|p| format!("{:.2}", p))
+                .unwrap_or_default(),
+        ),
+        Column::Updated => text(
+            p.last_modified
+                .or(p.build_date)
+                .map(format_date)
+                .unwrap_or_default(),
+        ),
+    }
+}
+
+fn spans_width(spans: &[Span]) -> u16 {
+    spans.iter().map(|s| s.width() as u16).sum()
+}
+
+// cuts the spans to `width` columns, ending in an ellipsis when anything
+// was left out
+pub fn truncate(spans: Vec<Span<'_>>, width: u16) -> Line<'_> {
+    if spans_width(&spans) <= width {
+        return Line::from(spans);
+    }
+    let mut left = width.saturating_sub(1);
+    let mut kept = Vec::new();
+    for span in spans {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            let w = text_width(c.encode_utf8(&mut [0; 4]));
+            if w > left {
+                break;
+            }
+            left -= w;
+            text.push(c);
+        }
+        let full = text.len() == span.content.len();
+        kept.push(Span::styled(text, span.style));
+        if !full {
+            break;
+        }
+    }
+    if width > 0 {
+        kept.push(Span::raw(ELLIPSIS));
+    }
+    Line::from(kept)
+}